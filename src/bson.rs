@@ -22,18 +22,22 @@
 //! BSON definition
 
 use std::{
+    convert::TryFrom,
     fmt::{self, Debug, Display},
     ops::{Deref, DerefMut},
+    slice,
 };
 
-use chrono::{offset::TimeZone, DateTime, Timelike, Utc};
-use serde_json::{json, Value};
+use bitflags::bitflags;
+use chrono::{offset::TimeZone, DateTime, Datelike, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
 
-#[cfg(feature = "decimal128")]
-use crate::decimal128::Decimal128;
 use crate::{
+    decimal128::Decimal128,
+    decoder::{DecoderError, DecoderResult},
+    encoder,
     oid,
-    ordered::OrderedDocument,
+    ordered::{OrderedDocument, OrderedDocumentIterator},
     spec::{BinarySubtype, ElementType},
 };
 
@@ -73,7 +77,6 @@ pub enum Bson {
     /// Symbol (Deprecated)
     Symbol(String),
     /// [128-bit decimal floating point](https://github.com/mongodb/specifications/blob/master/source/bson-decimal128/decimal128.rst)
-    #[cfg(feature = "decimal128")]
     Decimal128(Decimal128),
     /// Undefined value (Deprecated)
     Undefined,
@@ -132,16 +135,10 @@ impl Display for Bson {
             Bson::TimeStamp(TimeStamp { time, increment }) => {
                 write!(fmt, "Timestamp({}, {})", time, increment)
             }
-            Bson::Binary(Binary { subtype, ref bytes }) => write!(
-                fmt,
-                "BinData({}, 0x{})",
-                u8::from(subtype),
-                hex::encode(bytes)
-            ),
+            Bson::Binary(ref binary) => write!(fmt, "{}", binary),
             Bson::ObjectId(ref id) => write!(fmt, "ObjectId(\"{}\")", id),
             Bson::UtcDatetime(date_time) => write!(fmt, "Date(\"{}\")", date_time),
             Bson::Symbol(ref sym) => write!(fmt, "Symbol(\"{}\")", sym),
-            #[cfg(feature = "decimal128")]
             Bson::Decimal128(ref d) => write!(fmt, "Decimal128({})", d),
             Bson::Undefined => write!(fmt, "undefined"),
             Bson::MinKey => write!(fmt, "MinKey"),
@@ -293,12 +290,94 @@ impl From<DateTime<Utc>> for Bson {
     }
 }
 
+impl From<UtcDateTime> for Bson {
+    fn from(a: UtcDateTime) -> Bson {
+        Bson::UtcDatetime(a.0)
+    }
+}
+
+#[cfg(feature = "time-0_3")]
+impl From<time_0_3::OffsetDateTime> for Bson {
+    fn from(a: time_0_3::OffsetDateTime) -> Bson {
+        Bson::from(UtcDateTime::from(a))
+    }
+}
+
+impl From<TimeStamp> for Bson {
+    fn from(a: TimeStamp) -> Bson {
+        Bson::TimeStamp(a)
+    }
+}
+
 impl From<DbPointer> for Bson {
     fn from(a: DbPointer) -> Bson {
         Bson::DbPointer(a)
     }
 }
 
+impl PartialEq<str> for Bson {
+    fn eq(&self, other: &str) -> bool {
+        match *self {
+            Bson::String(ref v) => v == other,
+            _ => false,
+        }
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Bson {
+    fn eq(&self, other: &&'a str) -> bool {
+        match *self {
+            Bson::String(ref v) => v == *other,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<String> for Bson {
+    fn eq(&self, other: &String) -> bool {
+        match *self {
+            Bson::String(ref v) => v == other,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<i32> for Bson {
+    fn eq(&self, other: &i32) -> bool {
+        match *self {
+            Bson::I32(v) => v == *other,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<i64> for Bson {
+    fn eq(&self, other: &i64) -> bool {
+        match *self {
+            Bson::I64(v) => v == *other,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<f64> for Bson {
+    fn eq(&self, other: &f64) -> bool {
+        match *self {
+            Bson::FloatingPoint(v) => v == *other,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<bool> for Bson {
+    fn eq(&self, other: &bool) -> bool {
+        match *self {
+            Bson::Boolean(v) => v == *other,
+            _ => false,
+        }
+    }
+}
+
 impl From<Value> for Bson {
     fn from(a: Value) -> Bson {
         match a {
@@ -321,19 +400,173 @@ impl From<Value> for Bson {
 
 impl From<Bson> for Value {
     fn from(bson: Bson) -> Self {
-        match bson {
+        bson.to_extended_json()
+    }
+}
+
+// Keys that only appear in extended JSON representations of BSON types. Query operators that
+// share the `$` prefix, such as `$regex` and `$type`, are deliberately left out.
+const EXTENDED_JSON_KEYS: [&str; 16] = [
+    "$oid",
+    "$symbol",
+    "$numberInt",
+    "$numberLong",
+    "$numberDouble",
+    "$numberDecimal",
+    "$binary",
+    "$code",
+    "$scope",
+    "$timestamp",
+    "$regularExpression",
+    "$dbPointer",
+    "$date",
+    "$minKey",
+    "$maxKey",
+    "$undefined",
+];
+
+impl Bson {
+    /// Get the `ElementType` of this value.
+    pub fn element_type(&self) -> ElementType {
+        match *self {
+            Bson::FloatingPoint(..) => ElementType::FloatingPoint,
+            Bson::String(..) => ElementType::Utf8String,
+            Bson::Array(..) => ElementType::Array,
+            Bson::Document(..) => ElementType::EmbeddedDocument,
+            Bson::Boolean(..) => ElementType::Boolean,
+            Bson::Null => ElementType::NullValue,
+            Bson::Regex(..) => ElementType::RegularExpression,
+            Bson::JavaScriptCode(..) => ElementType::JavaScriptCode,
+            Bson::JavaScriptCodeWithScope(..) => ElementType::JavaScriptCodeWithScope,
+            Bson::I32(..) => ElementType::Integer32Bit,
+            Bson::I64(..) => ElementType::Integer64Bit,
+            Bson::TimeStamp(..) => ElementType::TimeStamp,
+            Bson::Binary(..) => ElementType::Binary,
+            Bson::ObjectId(..) => ElementType::ObjectId,
+            Bson::UtcDatetime(..) => ElementType::UtcDatetime,
+            Bson::Symbol(..) => ElementType::Symbol,
+            Bson::Decimal128(..) => ElementType::Decimal128Bit,
+            Bson::Undefined => ElementType::Undefined,
+            Bson::MaxKey => ElementType::MaxKey,
+            Bson::MinKey => ElementType::MinKey,
+            Bson::DbPointer(..) => ElementType::DbPointer,
+        }
+    }
+
+    /// The number of bytes this value occupies when encoded as BSON. This covers the value alone;
+    /// a document element additionally takes one byte for the element type plus the key and its
+    /// null terminator.
+    pub fn encoded_len(&self) -> usize {
+        encoder::value_len(self)
+    }
+
+    /// A short, human-readable name for the type of this value, such as `"int32"`, `"document"`
+    /// or `"objectId"`, for use in diagnostics.
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Bson::FloatingPoint(..) => "double",
+            Bson::String(..) => "string",
+            Bson::Array(..) => "array",
+            Bson::Document(..) => "document",
+            Bson::Boolean(..) => "boolean",
+            Bson::Null => "null",
+            Bson::Regex(..) => "regex",
+            Bson::JavaScriptCode(..) => "javascript",
+            Bson::JavaScriptCodeWithScope(..) => "javascriptWithScope",
+            Bson::I32(..) => "int32",
+            Bson::I64(..) => "int64",
+            Bson::TimeStamp(..) => "timestamp",
+            Bson::Binary(..) => "binary",
+            Bson::ObjectId(..) => "objectId",
+            Bson::UtcDatetime(..) => "date",
+            Bson::Symbol(..) => "symbol",
+            Bson::Decimal128(..) => "decimal128",
+            Bson::Undefined => "undefined",
+            Bson::MaxKey => "maxKey",
+            Bson::MinKey => "minKey",
+            Bson::DbPointer(..) => "dbPointer",
+        }
+    }
+
+    /// Converts to plain JSON, giving up BSON type fidelity in favor of human-friendly output.
+    ///
+    /// Unlike the extended JSON produced by `Value::from`, `ObjectId`s are rendered as hex
+    /// strings, dates as RFC 3339 strings, binary data as base64 strings, and symbols and
+    /// JavaScript code as plain strings. Types without a natural JSON form, such as timestamps and
+    /// min/max keys, keep their extended JSON representation, as do dates outside of years 0
+    /// through 9999.
+    ///
+    /// ```rust
+    /// use bson::{bson, oid::ObjectId};
+    /// use serde_json::json;
+    ///
+    /// let id = ObjectId::with_string("5e5efe0b0000000000000000").unwrap();
+    /// let bson = bson!({ "_id": id, "n": 1 });
+    /// assert_eq!(bson.into_json(), json!({ "_id": "5e5efe0b0000000000000000", "n": 1 }));
+    /// ```
+    pub fn into_json(self) -> Value {
+        self.to_json()
+    }
+
+    /// Converts to plain JSON by reference, producing the same output as `into_json` without
+    /// consuming the value.
+    pub fn to_json(&self) -> Value {
+        match *self {
+            Bson::Array(ref v) => Value::Array(v.iter().map(Bson::to_json).collect()),
+            Bson::Document(ref v) => {
+                Value::Object(v.iter().map(|(k, v)| (k.clone(), v.to_json())).collect())
+            }
+            Bson::ObjectId(ref v) => Value::String(v.to_hex()),
+            // RFC 3339 only covers four-digit years, so dates outside of that range keep their
+            // extended JSON representation.
+            Bson::UtcDatetime(v) if (0..=9999).contains(&v.year()) => {
+                Value::String(v.to_rfc3339_opts(SecondsFormat::Millis, true))
+            }
+            Bson::Binary(Binary { ref bytes, .. }) => Value::String(base64::encode(bytes)),
+            Bson::Regex(..) => Value::String(self.to_string()),
+            Bson::JavaScriptCode(ref code) | Bson::Symbol(ref code) => Value::String(code.clone()),
+            Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+                ref code,
+                ref scope,
+            }) => json!({
+                "code": code,
+                "scope": Value::Object(
+                    scope.iter().map(|(k, v)| (k.clone(), v.to_json())).collect()
+                ),
+            }),
+            Bson::Decimal128(ref v) => Value::String(v.to_string()),
+            Bson::Undefined => Value::Null,
+            _ => self.to_extended_json(),
+        }
+    }
+
+    /// Converts to extended JSON by reference, producing the same output as `Value::from`
+    /// without consuming the value.
+    pub fn to_extended_json(&self) -> Value {
+        match *self {
+            // JSON has no representation for non-finite numbers, so they keep their extended form.
+            Bson::FloatingPoint(v) if v.is_nan() => json!({ "$numberDouble": "NaN" }),
+            Bson::FloatingPoint(v) if v.is_infinite() => json!({
+                "$numberDouble": if v > 0.0 { "Infinity" } else { "-Infinity" }
+            }),
             Bson::FloatingPoint(v) => json!(v),
-            Bson::String(v) => json!(v),
-            Bson::Array(v) => json!(v),
-            Bson::Document(v) => json!(v),
+            Bson::String(ref v) => json!(v),
+            Bson::Array(ref v) => json!(v),
+            Bson::Document(ref v) => json!(v),
             Bson::Boolean(v) => json!(v),
             Bson::Null => Value::Null,
-            Bson::Regex(Regex { pattern, options }) => json!({
+            Bson::Regex(Regex {
+                ref pattern,
+                ref options,
+            }) => json!({
                 "$regex": pattern,
                 "$options": options
             }),
-            Bson::JavaScriptCode(code) => json!({ "$code": code }),
-            Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope { code, scope }) => json!({
+            Bson::JavaScriptCode(ref code) => json!({ "$code": code }),
+            Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+                ref code,
+                ref scope,
+            }) => json!({
                 "$code": code,
                 "scope": scope
             }),
@@ -350,15 +583,14 @@ impl From<Bson> for Value {
                     "$binary": hex::encode(bytes),
                 })
             }
-            Bson::ObjectId(v) => json!({"$oid": v.to_string()}),
+            Bson::ObjectId(ref v) => json!({"$oid": v.to_string()}),
             Bson::UtcDatetime(v) => json!({
                 "$date": {
-                    "$numberLong": (v.timestamp() * 1000) + ((v.nanosecond() / 1_000_000) as i64)
+                    "$numberLong": UtcDateTime(v).to_millis()
                 }
             }),
             // FIXME: Don't know what is the best way to encode Symbol type
-            Bson::Symbol(v) => json!({ "$symbol": v }),
-            #[cfg(feature = "decimal128")]
+            Bson::Symbol(ref v) => json!({ "$symbol": v }),
             Bson::Decimal128(ref v) => json!({ "$numberDecimal": v.to_string() }),
             Bson::Undefined => json!({ "$undefined": true }),
             Bson::MinKey => json!({ "$minKey": 1 }),
@@ -369,42 +601,15 @@ impl From<Bson> for Value {
             }) => json!({ "$dbPointer": { "$ref": namespace, "$id": id.to_string() } }),
         }
     }
-}
-
-impl Bson {
-    /// Get the `ElementType` of this value.
-    pub fn element_type(&self) -> ElementType {
-        match *self {
-            Bson::FloatingPoint(..) => ElementType::FloatingPoint,
-            Bson::String(..) => ElementType::Utf8String,
-            Bson::Array(..) => ElementType::Array,
-            Bson::Document(..) => ElementType::EmbeddedDocument,
-            Bson::Boolean(..) => ElementType::Boolean,
-            Bson::Null => ElementType::NullValue,
-            Bson::Regex(..) => ElementType::RegularExpression,
-            Bson::JavaScriptCode(..) => ElementType::JavaScriptCode,
-            Bson::JavaScriptCodeWithScope(..) => ElementType::JavaScriptCodeWithScope,
-            Bson::I32(..) => ElementType::Integer32Bit,
-            Bson::I64(..) => ElementType::Integer64Bit,
-            Bson::TimeStamp(..) => ElementType::TimeStamp,
-            Bson::Binary(..) => ElementType::Binary,
-            Bson::ObjectId(..) => ElementType::ObjectId,
-            Bson::UtcDatetime(..) => ElementType::UtcDatetime,
-            Bson::Symbol(..) => ElementType::Symbol,
-            #[cfg(feature = "decimal128")]
-            Bson::Decimal128(..) => ElementType::Decimal128Bit,
-            Bson::Undefined => ElementType::Undefined,
-            Bson::MaxKey => ElementType::MaxKey,
-            Bson::MinKey => ElementType::MinKey,
-            Bson::DbPointer(..) => ElementType::DbPointer,
-        }
-    }
 
     /// Converts to extended format.
     /// This function mainly used for [extended JSON format](https://docs.mongodb.com/manual/reference/mongodb-extended-json/).
+    ///
+    /// Returns an error for types that are represented directly rather than as an extended
+    /// document, such as strings, numbers, documents and arrays.
     #[doc(hidden)]
-    pub fn to_extended_document(&self) -> Document {
-        match *self {
+    pub fn to_extended_document(&self) -> DecoderResult<Document> {
+        let doc = match *self {
             Bson::Regex(Regex {
                 ref pattern,
                 ref options,
@@ -429,9 +634,10 @@ impl Bson {
                 }
             }
             Bson::TimeStamp(TimeStamp { time, increment }) => {
+                // Widen to i64 so that values with the high bit set don't turn negative.
                 doc! {
-                    "t": time,
-                    "i": increment
+                    "t": i64::from(time),
+                    "i": i64::from(increment)
                 }
             }
             Bson::Binary(Binary { subtype, ref bytes }) => {
@@ -449,7 +655,7 @@ impl Bson {
             Bson::UtcDatetime(ref v) => {
                 doc! {
                     "$date": {
-                        "$numberLong" => (v.timestamp() * 1000) + v.nanosecond() as i64 / 1_000_000,
+                        "$numberLong" => UtcDateTime(*v).to_millis(),
                     }
                 }
             }
@@ -458,7 +664,6 @@ impl Bson {
                     "$symbol": v.to_owned(),
                 }
             }
-            #[cfg(feature = "decimal128")]
             Bson::Decimal128(ref v) => {
                 doc! {
                     "$numberDecimal" => (v.to_string())
@@ -490,13 +695,19 @@ impl Bson {
                     }
                 }
             }
-            _ => panic!("Attempted conversion of invalid data type: {}", self),
-        }
+            _ => {
+                return Err(DecoderError::InvalidType(format!(
+                    "{:?} has no extended document representation",
+                    self.element_type()
+                )))
+            }
+        };
+
+        Ok(doc)
     }
 
     /// Converts from extended format.
     /// This function is mainly used for [extended JSON format](https://docs.mongodb.com/manual/reference/mongodb-extended-json/).
-    #[cfg(feature = "decimal128")]
     #[doc(hidden)]
     pub fn from_extended_document(values: Document) -> Bson {
         if values.len() == 2 {
@@ -531,38 +742,95 @@ impl Bson {
                     });
                 }
             } else if let (Ok(hex), Ok(t)) = (values.get_str("$binary"), values.get_i64("type")) {
-                let ttype = t as u8;
-                return Bson::Binary(Binary {
-                    subtype: From::from(ttype),
-                    bytes: hex::decode(hex.as_bytes())
-                        .expect("$binary value is not a valid Hex encoded bytes"),
-                });
+                // Types that don't fit in a byte are not binary subtypes.
+                if let Ok(ttype) = u8::try_from(t) {
+                    return Bson::Binary(Binary {
+                        subtype: From::from(ttype),
+                        bytes: hex::decode(hex.as_bytes())
+                            .expect("$binary value is not a valid Hex encoded bytes"),
+                    });
+                }
             }
         } else if values.len() == 1 {
             if let Ok(code) = values.get_str("$code") {
                 return Bson::JavaScriptCode(code.to_owned());
             } else if let Ok(hex) = values.get_str("$oid") {
                 return Bson::ObjectId(oid::ObjectId::with_string(hex).unwrap());
-            } else if let Ok(long) = values
-                .get_document("$date")
-                .and_then(|inner| inner.get_i64("$numberLong"))
-            {
-                return Bson::UtcDatetime(
-                    Utc.timestamp(long / 1000, ((long % 1000) * 1_000_000) as u32),
-                );
+            } else if let Ok(binary) = values.get_document("$binary") {
+                if let (2, Ok(data), Ok(subtype)) = (
+                    binary.len(),
+                    binary.get_str("base64"),
+                    binary.get_str("subType"),
+                ) {
+                    if let (Ok(bytes), Some(subtype)) =
+                        (base64::decode(data), parse_binary_subtype(subtype))
+                    {
+                        return Bson::Binary(Binary { subtype, bytes });
+                    }
+                }
+            } else if let Some(timestamp) = values.get("$timestamp") {
+                // Nested values converted from JSON have already become a timestamp.
+                match *timestamp {
+                    Bson::TimeStamp(timestamp) => return Bson::TimeStamp(timestamp),
+                    Bson::Document(ref inner) => {
+                        if let timestamp @ Bson::TimeStamp(..) =
+                            Bson::from_extended_document(inner.clone())
+                        {
+                            return timestamp;
+                        }
+                    }
+                    _ => {}
+                }
+            } else if let Some(long) = values.get("$date").and_then(Bson::as_date_millis) {
+                if let Some(date) = UtcDateTime::try_from_millis(long) {
+                    return Bson::UtcDatetime(date.0);
+                }
+            } else if let Ok(date) = values.get_str("$date") {
+                if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+                    return Bson::UtcDatetime(date.with_timezone(&Utc));
+                }
+            } else if let Ok(int) = values.get_str("$numberInt") {
+                if let Ok(int) = int.parse() {
+                    return Bson::I32(int);
+                }
+            } else if let Ok(long) = values.get_str("$numberLong") {
+                if let Ok(long) = long.parse() {
+                    return Bson::I64(long);
+                }
+            } else if let Ok(long) = values.get_i64("$numberLong") {
+                return Bson::I64(long);
+            } else if let Ok(double) = values.get_str("$numberDouble") {
+                if let Ok(double) = double.parse() {
+                    return Bson::FloatingPoint(double);
+                }
+            } else if let Ok(regex) = values.get_document("$regularExpression") {
+                if let (2, Ok(pattern), Ok(options)) = (
+                    regex.len(),
+                    regex.get_str("pattern"),
+                    regex.get_str("options"),
+                ) {
+                    return Bson::Regex(Regex {
+                        pattern: pattern.to_owned(),
+                        options: options.to_owned(),
+                    });
+                }
             } else if let Ok(sym) = values.get_str("$symbol") {
                 return Bson::Symbol(sym.to_owned());
-            } else if let Ok(dec) = values.get_str("$numberDecimal") {
-                return Bson::Decimal128(dec.parse::<Decimal128>().unwrap());
+            } else if let Some(dec) = values
+                .get_str("$numberDecimal")
+                .ok()
+                .and_then(|dec| dec.parse::<Decimal128>().ok())
+            {
+                return Bson::Decimal128(dec);
             } else if let Ok(undefined) = values.get_bool("$undefined") {
                 if undefined {
                     return Bson::Undefined;
                 }
-            } else if let Ok(min) = values.get_i64("$minKey") {
+            } else if let Some(min) = values.get("$minKey").and_then(Bson::as_key_marker) {
                 if min == 1 {
                     return Bson::MinKey;
                 }
-            } else if let Ok(max) = values.get_i64("$maxKey") {
+            } else if let Some(max) = values.get("$maxKey").and_then(Bson::as_key_marker) {
                 if max == 1 {
                     return Bson::MaxKey;
                 }
@@ -581,92 +849,272 @@ impl Bson {
         Bson::Document(values)
     }
 
-    /// Converts from extended format.
-    /// This function is mainly used for [extended JSON format](https://docs.mongodb.com/manual/reference/mongodb-extended-json/).
-    #[cfg(not(feature = "decimal128"))]
-    #[doc(hidden)]
-    pub fn from_extended_document(values: Document) -> Bson {
-        if values.len() == 2 {
-            if let (Ok(pat), Ok(opt)) = (values.get_str("$regex"), values.get_str("$options")) {
-                return Bson::Regex(Regex {
-                    pattern: pat.to_owned(),
-                    options: opt.to_owned(),
-                });
-            } else if let (Ok(code), Ok(scope)) =
-                (values.get_str("$code"), values.get_document("$scope"))
-            {
-                return Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
-                    code: code.to_owned(),
-                    scope: scope.to_owned(),
-                });
-            } else if let (Ok(time), Ok(increment)) = (values.get_i32("t"), values.get_i32("i")) {
-                if time >= 0 && increment >= 0 {
-                    return Bson::TimeStamp(TimeStamp {
-                        time: time as u32,
-                        increment: increment as u32,
-                    });
-                }
-            } else if let (Ok(time), Ok(increment)) = (values.get_i64("t"), values.get_i64("i")) {
-                if time >= 0
-                    && time <= std::u32::MAX as i64
-                    && increment >= 0
-                    && increment <= std::u32::MAX as i64
-                {
-                    return Bson::TimeStamp(TimeStamp {
-                        time: time as u32,
-                        increment: increment as u32,
-                    });
+    /// Parses a string of [extended JSON](https://docs.mongodb.com/manual/reference/mongodb-extended-json/)
+    /// into a `Bson` value, accepting both canonical and relaxed forms.
+    ///
+    /// ```rust
+    /// use bson::Bson;
+    ///
+    /// let bson = Bson::from_extended_json_str(r#"{ "$numberInt": "5" }"#).unwrap();
+    /// assert_eq!(bson, Bson::I32(5));
+    /// ```
+    ///
+    /// Unlike converting a `serde_json::Value` with `Bson::from`, any document that still contains
+    /// an extended JSON key such as `$date` or `$code` after conversion is reported as an error
+    /// rather than being passed through as a plain document.
+    pub fn from_extended_json_str(s: &str) -> DecoderResult<Bson> {
+        let value: Value =
+            serde_json::from_str(s).map_err(|e| DecoderError::SyntaxError(e.to_string()))?;
+        Bson::from_extended_json_value(value)
+    }
+
+    /// Converts a `serde_json::Value` into `Bson` without interpreting extended JSON, so objects
+    /// such as `{ "$oid": "..." }` become plain documents rather than the types they describe.
+    /// Numbers and other scalars are converted as by `Bson::from`.
+    pub fn from_value_no_parse(value: Value) -> Bson {
+        match value {
+            Value::Array(x) => Bson::Array(x.into_iter().map(Bson::from_value_no_parse).collect()),
+            Value::Object(x) => Bson::Document(
+                x.into_iter()
+                    .map(|(k, v)| (k, Bson::from_value_no_parse(v)))
+                    .collect(),
+            ),
+            other => other.into(),
+        }
+    }
+
+    /// Converts a `serde_json::Value` holding [canonical extended JSON](https://github.com/mongodb/specifications/blob/master/source/extended-json.rst)
+    /// into a `Bson` value, rejecting the relaxed and legacy forms that `Bson::from` also
+    /// accepts.
+    ///
+    /// Numbers must be wrapped in `$numberInt`, `$numberLong` or `$numberDouble`, and `$date` must
+    /// hold `{ "$numberLong": "..." }` rather than an RFC 3339 string or a bare number.
+    ///
+    /// ```rust
+    /// use bson::{bson, Bson};
+    /// use serde_json::json;
+    ///
+    /// let canonical = json!({ "x": { "$numberInt": "5" } });
+    /// assert_eq!(Bson::from_canonical_extjson(canonical).unwrap(), bson!({ "x": 5 }));
+    ///
+    /// assert!(Bson::from_canonical_extjson(json!({ "x": 5 })).is_err());
+    /// ```
+    pub fn from_canonical_extjson(value: Value) -> DecoderResult<Bson> {
+        match value {
+            Value::Number(x) => Err(DecoderError::InvalidType(format!(
+                "malformed canonical extended JSON: bare number {} is not wrapped in $numberInt, \
+                 $numberLong or $numberDouble",
+                x
+            ))),
+            Value::Array(x) => x
+                .into_iter()
+                .map(Bson::from_canonical_extjson)
+                .collect::<DecoderResult<_>>()
+                .map(Bson::Array),
+            Value::Object(x) => {
+                if x.keys().any(|k| EXTENDED_JSON_KEYS.contains(&k.as_str())) {
+                    Bson::from_canonical_extjson_object(x)
+                } else {
+                    Bson::from_canonical_extjson_document(x).map(Bson::Document)
                 }
-            } else if let (Ok(hex), Ok(t)) = (values.get_str("$binary"), values.get_i64("type")) {
-                let ttype = t as u8;
-                return Bson::Binary(Binary {
-                    subtype: From::from(ttype),
-                    bytes: hex::decode(hex.as_bytes())
-                        .expect("$binary value is not a valid Hex encoded bytes"),
-                });
             }
-        } else if values.len() == 1 {
-            if let Ok(code) = values.get_str("$code") {
-                return Bson::JavaScriptCode(code.to_owned());
-            } else if let Ok(hex) = values.get_str("$oid") {
-                return Bson::ObjectId(oid::ObjectId::with_string(hex).unwrap());
-            } else if let Ok(long) = values
-                .get_document("$date")
-                .and_then(|inner| inner.get_i64("$numberLong"))
-            {
-                return Bson::UtcDatetime(
-                    Utc.timestamp(long / 1000, ((long % 1000) * 1_000_000) as u32),
-                );
-            } else if let Ok(sym) = values.get_str("$symbol") {
-                return Bson::Symbol(sym.to_owned());
-            } else if let Ok(undefined) = values.get_bool("$undefined") {
-                if undefined {
-                    return Bson::Undefined;
+            other => Ok(other.into()),
+        }
+    }
+
+    fn from_canonical_extjson_document(x: Map<String, Value>) -> DecoderResult<Document> {
+        x.into_iter()
+            .map(|(k, v)| Ok((k, Bson::from_canonical_extjson(v)?)))
+            .collect()
+    }
+
+    // Converts an object that uses extended JSON keys, which must form exactly one canonical type.
+    fn from_canonical_extjson_object(x: Map<String, Value>) -> DecoderResult<Bson> {
+        let bson = if x.len() == 2 && x.contains_key("$code") && x.contains_key("$scope") {
+            match (&x["$code"], &x["$scope"]) {
+                (Value::String(code), Value::Object(scope)) => {
+                    Some(Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+                        code: code.clone(),
+                        scope: Bson::from_canonical_extjson_document(scope.clone())?,
+                    }))
                 }
-            } else if let Ok(min) = values.get_i64("$minKey") {
-                if min == 1 {
-                    return Bson::MinKey;
+                _ => None,
+            }
+        } else if x.len() == 1 {
+            let (key, value) = x.iter().next().unwrap();
+            Bson::from_canonical_extjson_field(key, value)
+        } else {
+            None
+        };
+
+        bson.ok_or_else(|| {
+            DecoderError::InvalidValue(format!(
+                "malformed canonical extended JSON: {}",
+                Value::Object(x)
+            ))
+        })
+    }
+
+    // Converts the value of a single-key extended JSON object, returning `None` if it is not in
+    // canonical form.
+    fn from_canonical_extjson_field(key: &str, value: &Value) -> Option<Bson> {
+        let bson = match key {
+            "$oid" => Bson::ObjectId(oid::ObjectId::with_string(value.as_str()?).ok()?),
+            "$symbol" => Bson::Symbol(value.as_str()?.to_owned()),
+            "$code" => Bson::JavaScriptCode(value.as_str()?.to_owned()),
+            "$numberInt" => Bson::I32(value.as_str()?.parse().ok()?),
+            "$numberLong" => Bson::I64(value.as_str()?.parse().ok()?),
+            "$numberDouble" => match value.as_str()? {
+                "Infinity" => Bson::FloatingPoint(f64::INFINITY),
+                "-Infinity" => Bson::FloatingPoint(f64::NEG_INFINITY),
+                "NaN" => Bson::FloatingPoint(f64::NAN),
+                // Rust also parses spellings such as "inf", which are not canonical.
+                s if s
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b)) =>
+                {
+                    Bson::FloatingPoint(s.parse().ok()?)
                 }
-            } else if let Ok(max) = values.get_i64("$maxKey") {
-                if max == 1 {
-                    return Bson::MaxKey;
+                _ => return None,
+            },
+            "$numberDecimal" => Bson::Decimal128(value.as_str()?.parse().ok()?),
+            "$binary" => {
+                let fields = canonical_fields(value, &["base64", "subType"])?;
+                Bson::Binary(Binary {
+                    subtype: parse_binary_subtype(fields[1].as_str()?)?,
+                    bytes: base64::decode(fields[0].as_str()?).ok()?,
+                })
+            }
+            "$timestamp" => {
+                let fields = canonical_fields(value, &["t", "i"])?;
+                Bson::TimeStamp(TimeStamp {
+                    time: u32::try_from(fields[0].as_u64()?).ok()?,
+                    increment: u32::try_from(fields[1].as_u64()?).ok()?,
+                })
+            }
+            "$regularExpression" => {
+                let fields = canonical_fields(value, &["pattern", "options"])?;
+                Bson::Regex(Regex {
+                    pattern: fields[0].as_str()?.to_owned(),
+                    options: fields[1].as_str()?.to_owned(),
+                })
+            }
+            "$dbPointer" => {
+                let fields = canonical_fields(value, &["$ref", "$id"])?;
+                let id = canonical_fields(fields[1], &["$oid"])?;
+                Bson::DbPointer(DbPointer {
+                    namespace: fields[0].as_str()?.to_owned(),
+                    id: oid::ObjectId::with_string(id[0].as_str()?).ok()?,
+                })
+            }
+            "$date" => {
+                let millis = canonical_fields(value, &["$numberLong"])?[0].as_str()?;
+                Bson::UtcDatetime(UtcDateTime::try_from_millis(millis.parse().ok()?)?.0)
+            }
+            "$minKey" if value.as_u64() == Some(1) => Bson::MinKey,
+            "$maxKey" if value.as_u64() == Some(1) => Bson::MaxKey,
+            "$undefined" if value.as_bool() == Some(true) => Bson::Undefined,
+            _ => return None,
+        };
+
+        Some(bson)
+    }
+
+    fn from_extended_json_value(value: Value) -> DecoderResult<Bson> {
+        match value {
+            Value::Array(x) => x
+                .into_iter()
+                .map(Bson::from_extended_json_value)
+                .collect::<DecoderResult<_>>()
+                .map(Bson::Array),
+            Value::Object(x) => {
+                let values = x
+                    .into_iter()
+                    .map(|(k, v)| Ok((k, Bson::from_extended_json_value(v)?)))
+                    .collect::<DecoderResult<_>>()?;
+                Bson::try_from_extended_document(values)
+            }
+            other => Ok(other.into()),
+        }
+    }
+
+    /// Converts from extended format like `from_extended_document`, but returns an error instead
+    /// of a plain document when `values` uses extended JSON keys without matching any extended
+    /// type, such as a `$code` alongside unrelated fields or a `$date` with an invalid value.
+    #[doc(hidden)]
+    pub fn try_from_extended_document(values: Document) -> DecoderResult<Bson> {
+        match Bson::from_extended_document(values) {
+            Bson::Document(doc) => {
+                // A `$code` may only be accompanied by a `$scope`, so name the keys allowed.
+                if doc.contains_key("$code") {
+                    if let Some(key) = doc.keys().find(|k| *k != "$code" && *k != "$scope") {
+                        return Err(DecoderError::InvalidValue(format!(
+                            "malformed extended JSON: unexpected \"{}\" alongside \"$code\", \
+                             expected only \"$code\" and \"$scope\"",
+                            key
+                        )));
+                    }
                 }
-            } else if let Ok(db_pointer) = values.get_document("$dbPointer") {
-                if let (Ok(namespace), Ok(id)) =
-                    (db_pointer.get_str("$ref"), db_pointer.get_str("$id"))
+
+                if let Some(key) = doc
+                    .keys()
+                    .find(|k| EXTENDED_JSON_KEYS.contains(&k.as_str()))
                 {
-                    return Bson::DbPointer(DbPointer {
-                        namespace: namespace.to_owned(),
-                        id: oid::ObjectId::with_string(id).unwrap(),
-                    });
+                    return Err(DecoderError::InvalidValue(format!(
+                        "malformed extended JSON: unexpected \"{}\" in a document with {} {}",
+                        key,
+                        doc.len(),
+                        if doc.len() == 1 { "key" } else { "keys" }
+                    )));
                 }
+                Ok(Bson::Document(doc))
             }
+            other => Ok(other),
         }
+    }
 
-        Bson::Document(values)
+    // The millisecond value of a `$date`, which is either nested as `{ "$numberLong": ... }` or,
+    // when that nested document has already been converted from JSON, a plain 64-bit integer.
+    fn as_date_millis(&self) -> Option<i64> {
+        match *self {
+            Bson::I64(v) => Some(v),
+            Bson::Document(ref inner) if inner.len() == 1 => match inner.get("$numberLong")? {
+                Bson::I64(v) => Some(*v),
+                Bson::String(v) => v.parse().ok(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // `$minKey` and `$maxKey` markers are written as 32-bit integers by `to_extended_document`,
+    // but parsed JSON produces 64-bit integers.
+    fn as_key_marker(&self) -> Option<i64> {
+        match *self {
+            Bson::I32(v) => Some(v as i64),
+            Bson::I64(v) => Some(v),
+            _ => None,
+        }
     }
 }
 
+// Returns the values of `keys`, in order, if `value` is an object with exactly those keys.
+fn canonical_fields<'a>(value: &'a Value, keys: &[&str]) -> Option<Vec<&'a Value>> {
+    let object = value.as_object()?;
+    if object.len() != keys.len() {
+        return None;
+    }
+    keys.iter().map(|key| object.get(*key)).collect()
+}
+
+// Parses the `subType` of a canonical extended JSON `$binary`, a one or two digit hex string.
+fn parse_binary_subtype(s: &str) -> Option<BinarySubtype> {
+    if s.is_empty() || s.len() > 2 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u8::from_str_radix(s, 16).ok().map(BinarySubtype::from)
+}
+
 /// Value helpers
 impl Bson {
     /// If `Bson` is `FloatingPoint`, return its value. Returns `None` otherwise
@@ -685,6 +1133,14 @@ impl Bson {
         }
     }
 
+    /// If `Bson` is `Binary`, return its bytes, whatever the subtype. Returns `None` otherwise
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match *self {
+            Bson::Binary(Binary { ref bytes, .. }) => Some(bytes),
+            _ => None,
+        }
+    }
+
     /// If `Bson` is `String`, return a mutable reference to its value. Returns `None` otherwise
     pub fn as_str_mut(&mut self) -> Option<&mut str> {
         match *self {
@@ -725,6 +1181,62 @@ impl Bson {
         }
     }
 
+    /// If `Bson` is `Array`, return its value without cloning. Returns the original value as the
+    /// error otherwise.
+    pub fn try_into_array(self) -> Result<Array, Bson> {
+        match self {
+            Bson::Array(v) => Ok(v),
+            other => Err(other),
+        }
+    }
+
+    /// If `Bson` is `Document`, return its value without cloning. Returns the original value as
+    /// the error otherwise.
+    pub fn try_into_document(self) -> Result<Document, Bson> {
+        match self {
+            Bson::Document(v) => Ok(v),
+            other => Err(other),
+        }
+    }
+
+    /// If `Bson` is `Document`, return a clone of its value. Returns an `InvalidType` error
+    /// naming the actual type otherwise.
+    pub fn to_document(&self) -> DecoderResult<Document> {
+        match *self {
+            Bson::Document(ref v) => Ok(v.clone()),
+            ref other => Err(DecoderError::InvalidType(format!(
+                "expected EmbeddedDocument, found {:?}",
+                other.element_type()
+            ))),
+        }
+    }
+
+    /// Constructs an empty `Array` with room for at least `capacity` elements.
+    pub fn array_with_capacity(capacity: usize) -> Bson {
+        Bson::Array(Vec::with_capacity(capacity))
+    }
+
+    /// If `Bson` is `Array`, append `value` to it. Returns `value` as an error otherwise
+    pub fn push(&mut self, value: impl Into<Bson>) -> Result<(), Bson> {
+        match *self {
+            Bson::Array(ref mut v) => {
+                v.push(value.into());
+                Ok(())
+            }
+            _ => Err(value.into()),
+        }
+    }
+
+    /// If `Bson` is `Array`, return an iterator over its elements. Returns `None` otherwise
+    pub fn array_iter(&self) -> Option<slice::Iter<'_, Bson>> {
+        self.as_array().map(|v| v.iter())
+    }
+
+    /// If `Bson` is `Document`, return an iterator over its entries. Returns `None` otherwise
+    pub fn document_iter(&self) -> Option<OrderedDocumentIterator<'_>> {
+        self.as_document().map(|v| v.iter())
+    }
+
     /// If `Bson` is `Boolean`, return its value. Returns `None` otherwise
     pub fn as_bool(&self) -> Option<bool> {
         match *self {
@@ -749,6 +1261,26 @@ impl Bson {
         }
     }
 
+    /// If `Bson` is `I32` or `I64`, return its value widened to an `i64`. Returns `None` otherwise
+    pub fn as_i64_lossy(&self) -> Option<i64> {
+        match *self {
+            Bson::I32(v) => Some(i64::from(v)),
+            Bson::I64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// If `Bson` is `FloatingPoint`, `I32` or `I64`, return its value as an `f64`. Large `I64`
+    /// values may lose precision. Returns `None` otherwise
+    pub fn as_f64_lossy(&self) -> Option<f64> {
+        match *self {
+            Bson::FloatingPoint(v) => Some(v),
+            Bson::I32(v) => Some(f64::from(v)),
+            Bson::I64(v) => Some(v as f64),
+            _ => None,
+        }
+    }
+
     /// If `Bson` is `Objectid`, return its value. Returns `None` otherwise
     pub fn as_object_id(&self) -> Option<&oid::ObjectId> {
         match *self {
@@ -782,6 +1314,16 @@ impl Bson {
         }
     }
 
+    /// If `Bson` is `UtcDateTime`, return its value as a `time::OffsetDateTime`. Returns `None`
+    /// otherwise, or if the value is outside the range supported by `time`.
+    #[cfg(feature = "time-0_3")]
+    pub fn to_time_0_3(&self) -> Option<time_0_3::OffsetDateTime> {
+        match *self {
+            Bson::UtcDatetime(v) => UtcDateTime(v).to_time_0_3(),
+            _ => None,
+        }
+    }
+
     /// If `Bson` is `Symbol`, return its value. Returns `None` otherwise
     pub fn as_symbol(&self) -> Option<&str> {
         match *self {
@@ -820,10 +1362,105 @@ impl Bson {
             _ => None,
         }
     }
+
+    /// Compares two values, treating numeric values (`FloatingPoint`, `I32` and `I64`) as equal
+    /// if they differ by no more than `epsilon`. Documents and arrays are compared element by
+    /// element; all other values must be exactly equal.
+    pub fn approx_eq(&self, other: &Bson, epsilon: f64) -> bool {
+        match (self, other) {
+            (Bson::Document(a), Bson::Document(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b.iter())
+                        .all(|((ka, va), (kb, vb))| ka == kb && va.approx_eq(vb, epsilon))
+            }
+            (Bson::Array(a), Bson::Array(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b.iter())
+                        .all(|(va, vb)| va.approx_eq(vb, epsilon))
+            }
+            _ => match (self.as_f64_lossy(), other.as_f64_lossy()) {
+                (Some(a), Some(b)) => a == b || (a - b).abs() <= epsilon,
+                _ => self == other,
+            },
+        }
+    }
+
+    /// Looks up a nested value by a dotted path such as `"a.b.0"`, descending into embedded
+    /// documents by key and into arrays by index. Returns `None` if a segment is missing or if
+    /// the path continues past a value that is neither a document nor an array.
+    pub fn get_path(&self, path: &str) -> Option<&Bson> {
+        path.split('.')
+            .try_fold(self, |value, segment| match *value {
+                Bson::Document(ref doc) => doc.get(segment),
+                Bson::Array(ref arr) => segment.parse::<usize>().ok().and_then(|i| arr.get(i)),
+                _ => None,
+            })
+    }
+
+    /// Looks up a nested value by a dotted path, returning a mutable reference. See `get_path`.
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Bson> {
+        path.split('.')
+            .try_fold(self, |value, segment| match *value {
+                Bson::Document(ref mut doc) => doc.get_mut(segment),
+                Bson::Array(ref mut arr) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(move |i| arr.get_mut(i)),
+                _ => None,
+            })
+    }
+
+    /// Calls `f` on this value and on every value nested inside it, in document order, along
+    /// with the path leading to it. Containers are visited before their contents, and the path
+    /// of this value itself is empty. Only embedded documents and arrays are descended into.
+    pub fn for_each<'a, F>(&'a self, mut f: F)
+    where
+        F: FnMut(&[PathSegment<'a>], &'a Bson),
+    {
+        fn walk<'a, F>(value: &'a Bson, path: &mut Vec<PathSegment<'a>>, f: &mut F)
+        where
+            F: FnMut(&[PathSegment<'a>], &'a Bson),
+        {
+            f(path, value);
+            match *value {
+                Bson::Document(ref doc) => {
+                    for (key, value) in doc {
+                        path.push(PathSegment::Key(key));
+                        walk(value, path, f);
+                        path.pop();
+                    }
+                }
+                Bson::Array(ref arr) => {
+                    for (index, value) in arr.iter().enumerate() {
+                        path.push(PathSegment::Index(index));
+                        walk(value, path, f);
+                        path.pop();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        walk(self, &mut Vec::new(), &mut f);
+    }
 }
 
-/// Represents a BSON timestamp value.
+/// A step in the path to a nested value, as passed to `Bson::for_each`.
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum PathSegment<'a> {
+    /// The key of a field in an embedded document.
+    Key(&'a str),
+    /// The index of an element in an array.
+    Index(usize),
+}
+
+/// Represents a BSON timestamp value.
+///
+/// Timestamps order by `time` first and then by `increment`, matching the ordering used by
+/// MongoDB.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
 pub struct TimeStamp {
     /// The number of seconds since the Unix epoch.
     pub time: u32,
@@ -834,18 +1471,29 @@ pub struct TimeStamp {
 }
 
 impl TimeStamp {
+    /// Constructs a timestamp from its `time` and `increment` components.
+    pub fn new(time: u32, increment: u32) -> TimeStamp {
+        TimeStamp { time, increment }
+    }
+
+    /// Packs the timestamp into the 64-bit integer whose little-endian encoding is its BSON wire
+    /// format, with `time` in the high 32 bits and `increment` in the low 32 bits.
+    ///
+    /// This is plain integer arithmetic; the byte order is applied when the integer is written.
     pub(crate) fn to_le_i64(self) -> i64 {
-        let upper = (self.time.to_le() as u64) << 32;
-        let lower = self.increment.to_le() as u64;
+        let upper = u64::from(self.time) << 32;
+        let lower = u64::from(self.increment);
 
         (upper | lower) as i64
     }
 
+    /// Unpacks a timestamp from the 64-bit integer read (as little-endian) from the BSON wire
+    /// format. The inverse of `to_le_i64`.
     pub(crate) fn from_le_i64(val: i64) -> Self {
-        let ts = val.to_le();
+        let ts = val as u64;
 
         TimeStamp {
-            time: ((ts as u64) >> 32) as u32,
+            time: (ts >> 32) as u32,
             increment: (ts & 0xFFFF_FFFF) as u32,
         }
     }
@@ -853,16 +1501,22 @@ impl TimeStamp {
 
 /// `DateTime` representation in struct for serde serialization
 ///
-/// Just a helper for convenience
+/// Just a helper for convenience. Human-readable formats represent it with the extended JSON
+/// `$date` form, and other formats with the number of milliseconds since the Unix epoch.
 ///
-/// ```rust,ignore
-/// use serde::{Serialize, Deserialize};
-/// use bson::UtcDateTime;
+/// ```rust
+/// use bson::{bson, UtcDateTime};
+/// use serde_derive::{Deserialize, Serialize};
 ///
-/// #[derive(Serialize, Deserialize)]
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
 /// struct Foo {
 ///     date_time: UtcDateTime,
 /// }
+///
+/// let foo = Foo { date_time: UtcDateTime::from_millis(1_577_836_800_000) };
+/// let bson = bson::to_bson(&foo).unwrap();
+/// assert_eq!(bson, bson!({ "date_time": *foo.date_time }));
+/// assert_eq!(bson::from_bson::<Foo>(bson).unwrap(), foo);
 /// ```
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
 pub struct UtcDateTime(pub DateTime<Utc>);
@@ -881,6 +1535,51 @@ impl DerefMut for UtcDateTime {
     }
 }
 
+impl UtcDateTime {
+    /// Returns the current time.
+    pub fn now() -> UtcDateTime {
+        UtcDateTime(Utc::now())
+    }
+
+    /// Constructs a datetime from a number of milliseconds since the Unix epoch, which is how BSON
+    /// represents datetimes. Negative values are before the epoch.
+    ///
+    /// Values beyond the range supported by `chrono` are clamped to the earliest or latest
+    /// representable time.
+    pub fn from_millis(millis: i64) -> UtcDateTime {
+        let bound = if millis < 0 {
+            DateTime::<Utc>::MIN_UTC
+        } else {
+            DateTime::<Utc>::MAX_UTC
+        };
+
+        UtcDateTime::try_from_millis(millis).unwrap_or(UtcDateTime(bound))
+    }
+
+    /// Returns the number of milliseconds since the Unix epoch, truncating any sub-millisecond
+    /// precision.
+    pub fn to_millis(&self) -> i64 {
+        self.0.timestamp_millis()
+    }
+
+    /// Converts the datetime to a `time::OffsetDateTime` in UTC, returning `None` if it is
+    /// outside the range supported by `time`.
+    #[cfg(feature = "time-0_3")]
+    pub fn to_time_0_3(&self) -> Option<time_0_3::OffsetDateTime> {
+        let nanos = i128::from(self.to_millis()) * 1_000_000;
+        time_0_3::OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+    }
+
+    /// Like `from_millis`, but returns `None` for values beyond the range supported by `chrono`.
+    pub(crate) fn try_from_millis(millis: i64) -> Option<UtcDateTime> {
+        // Round towards negative infinity so the sub-second part is never negative.
+        let secs = millis.div_euclid(1000);
+        let nanos = millis.rem_euclid(1000) as u32 * 1_000_000;
+
+        Utc.timestamp_opt(secs, nanos).single().map(UtcDateTime)
+    }
+}
+
 impl From<UtcDateTime> for DateTime<Utc> {
     fn from(utc: UtcDateTime) -> Self {
         utc.0
@@ -893,6 +1592,21 @@ impl From<DateTime<Utc>> for UtcDateTime {
     }
 }
 
+/// Truncates to millisecond precision, since that is all BSON datetimes can represent.
+///
+/// With the `large-dates` feature of `time`, times can lie beyond the range supported by
+/// `chrono`. Those are clamped to the earliest or latest representable time, as with
+/// `UtcDateTime::from_millis`.
+#[cfg(feature = "time-0_3")]
+impl From<time_0_3::OffsetDateTime> for UtcDateTime {
+    fn from(x: time_0_3::OffsetDateTime) -> Self {
+        // Round towards negative infinity, like the encoding of pre-epoch times.
+        let millis = x.unix_timestamp_nanos().div_euclid(1_000_000);
+        // Even `large-dates` years only reach about 3e16 milliseconds, well within an i64.
+        UtcDateTime::from_millis(millis as i64)
+    }
+}
+
 /// Represents a BSON regular expression value.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Regex {
@@ -909,6 +1623,123 @@ pub struct Regex {
     pub options: String,
 }
 
+bitflags! {
+    /// The options of a BSON regular expression, as parsed from its options string.
+    pub struct RegexFlags: u8 {
+        /// 'i': case insensitive matching.
+        const CASE_INSENSITIVE = 0b0000_0001;
+        /// 'l': make \w, \W, etc. locale dependent.
+        const LOCALE = 0b0000_0010;
+        /// 'm': multiline matching.
+        const MULTILINE = 0b0000_0100;
+        /// 's': dotall mode ('.' matches everything).
+        const DOTALL = 0b0000_1000;
+        /// 'u': make \w, \W, etc. match unicode.
+        const UNICODE = 0b0001_0000;
+        /// 'x': verbose mode.
+        const EXTENDED = 0b0010_0000;
+    }
+}
+
+// Option characters in the alphabetical order they must be stored in.
+const REGEX_OPTIONS: [(char, RegexFlags); 6] = [
+    ('i', RegexFlags::CASE_INSENSITIVE),
+    ('l', RegexFlags::LOCALE),
+    ('m', RegexFlags::MULTILINE),
+    ('s', RegexFlags::DOTALL),
+    ('u', RegexFlags::UNICODE),
+    ('x', RegexFlags::EXTENDED),
+];
+
+impl Regex {
+    /// Constructs a regex from a pattern and an options string.
+    pub fn new(pattern: impl Into<String>, options: impl Into<String>) -> Regex {
+        Regex {
+            pattern: pattern.into(),
+            options: options.into(),
+        }
+    }
+
+    /// Constructs a regex from a pattern and a set of flags, producing a correctly sorted
+    /// options string.
+    pub fn from_flags(pattern: impl Into<String>, flags: RegexFlags) -> Regex {
+        let options = REGEX_OPTIONS
+            .iter()
+            .filter(|&&(_, flag)| flags.contains(flag))
+            .map(|&(c, _)| c)
+            .collect();
+
+        Regex {
+            pattern: pattern.into(),
+            options,
+        }
+    }
+
+    /// Returns a copy of this regex with its options sorted into alphabetical order and any
+    /// duplicates removed, as BSON requires. Returns an error if the options contain a character
+    /// that is not a valid BSON regex option.
+    pub fn normalized(&self) -> DecoderResult<Regex> {
+        let mut flags = RegexFlags::empty();
+        for c in self.options.chars() {
+            match REGEX_OPTIONS.iter().find(|&&(o, _)| o == c) {
+                Some(&(_, flag)) => flags |= flag,
+                None => {
+                    return Err(DecoderError::InvalidValue(format!(
+                        "invalid regex option '{}'",
+                        c
+                    )))
+                }
+            }
+        }
+
+        Ok(Regex::from_flags(self.pattern.clone(), flags))
+    }
+
+    /// Parses the options string into a set of flags. Unrecognized option characters are
+    /// ignored.
+    pub fn flags(&self) -> RegexFlags {
+        let mut flags = RegexFlags::empty();
+        for c in self.options.chars() {
+            if let Some(&(_, flag)) = REGEX_OPTIONS.iter().find(|&&(o, _)| o == c) {
+                flags |= flag;
+            }
+        }
+        flags
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Regex {
+    /// Compiles this regex with the `regex` crate, translating the 'i', 'm', 's' and 'x' options
+    /// into the equivalent `regex::RegexBuilder` settings. Unicode matching is always enabled, so
+    /// 'u' has no additional effect.
+    ///
+    /// Returns an error if the options contain a character the `regex` crate has no equivalent
+    /// for, such as 'l', or if the pattern itself fails to compile.
+    pub fn compile(&self) -> DecoderResult<regex::Regex> {
+        let mut builder = regex::RegexBuilder::new(&self.pattern);
+        for c in self.options.chars() {
+            match c {
+                'i' => builder.case_insensitive(true),
+                'm' => builder.multi_line(true),
+                's' => builder.dot_matches_new_line(true),
+                'x' => builder.ignore_whitespace(true),
+                'u' => builder.unicode(true),
+                _ => {
+                    return Err(DecoderError::InvalidValue(format!(
+                        "unsupported regex option '{}'",
+                        c
+                    )))
+                }
+            };
+        }
+
+        builder
+            .build()
+            .map_err(|e| DecoderError::SyntaxError(e.to_string()))
+    }
+}
+
 /// Represents a BSON code with scope value.
 #[derive(Debug, Clone, PartialEq)]
 pub struct JavaScriptCodeWithScope {
@@ -916,6 +1747,21 @@ pub struct JavaScriptCodeWithScope {
     pub scope: Document,
 }
 
+impl JavaScriptCodeWithScope {
+    /// Constructs a code with scope value from its code and scope document.
+    pub fn new(code: impl Into<String>, scope: Document) -> JavaScriptCodeWithScope {
+        JavaScriptCodeWithScope {
+            code: code.into(),
+            scope,
+        }
+    }
+
+    /// Returns a mutable reference to the scope document.
+    pub fn scope_mut(&mut self) -> &mut Document {
+        &mut self.scope
+    }
+}
+
 /// Represents a BSON binary value.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Binary {
@@ -926,9 +1772,160 @@ pub struct Binary {
     pub bytes: Vec<u8>,
 }
 
+impl Binary {
+    /// Constructs a binary value with the given subtype.
+    pub fn new(subtype: BinarySubtype, bytes: Vec<u8>) -> Binary {
+        Binary { subtype, bytes }
+    }
+
+    /// Constructs a binary value of subtype `Generic`.
+    pub fn generic(bytes: Vec<u8>) -> Binary {
+        Binary::new(BinarySubtype::Generic, bytes)
+    }
+
+    /// Returns the hex encoding of all of the bytes. `Display` only shows a preview of long
+    /// values.
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.bytes)
+    }
+}
+
+/// The number of bytes shown by `Display` before a binary value is truncated.
+const BINARY_DISPLAY_PREVIEW: usize = 16;
+
+impl Display for Binary {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let subtype = u8::from(self.subtype);
+        if self.bytes.len() <= BINARY_DISPLAY_PREVIEW {
+            return write!(fmt, "BinData({}, 0x{})", subtype, self.to_hex());
+        }
+
+        // Keep large blobs from flooding logs.
+        write!(
+            fmt,
+            "BinData({}, 0x{}..., {} bytes)",
+            subtype,
+            hex::encode(&self.bytes[..BINARY_DISPLAY_PREVIEW]),
+            self.bytes.len()
+        )
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl Binary {
+    /// Constructs a binary value of subtype `Uuid` holding the bytes of `uuid`.
+    pub fn from_uuid(uuid: uuid::Uuid) -> Binary {
+        Binary {
+            subtype: BinarySubtype::Uuid,
+            bytes: uuid.as_bytes().to_vec(),
+        }
+    }
+
+    /// Converts a binary value of subtype `Uuid` back into a `uuid::Uuid`. Returns an error if the
+    /// subtype is anything else or the value is not exactly 16 bytes long.
+    pub fn to_uuid(&self) -> DecoderResult<uuid::Uuid> {
+        if self.subtype != BinarySubtype::Uuid {
+            return Err(DecoderError::InvalidType(format!(
+                "expected binary subtype Uuid, found {:?}",
+                self.subtype
+            )));
+        }
+
+        uuid::Uuid::from_slice(&self.bytes).map_err(|_| {
+            DecoderError::InvalidLength(
+                self.bytes.len(),
+                format!("expected 16 bytes for a UUID, found {}", self.bytes.len()),
+            )
+        })
+    }
+}
+
 /// Represents a DBPointer. (Deprecated)
 #[derive(Debug, Clone, PartialEq)]
 pub struct DbPointer {
     pub(crate) namespace: String,
     pub(crate) id: oid::ObjectId,
 }
+
+impl DbPointer {
+    /// Constructs a DBPointer referencing the document with the given id in `namespace`.
+    pub fn new(namespace: impl Into<String>, id: oid::ObjectId) -> DbPointer {
+        DbPointer {
+            namespace: namespace.into(),
+            id,
+        }
+    }
+
+    /// The namespace of the referenced document.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// The id of the referenced document.
+    pub fn id(&self) -> &oid::ObjectId {
+        &self.id
+    }
+}
+
+#[cfg(all(test, feature = "time-0_3"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn time_0_3_roundtrip() {
+        let time =
+            time_0_3::OffsetDateTime::from_unix_timestamp_nanos(1_577_836_800_123_000_000).unwrap();
+        let bson = Bson::from(time);
+        assert_eq!(
+            bson,
+            Bson::from(UtcDateTime::from_millis(1_577_836_800_123))
+        );
+        assert_eq!(bson.to_time_0_3(), Some(time));
+
+        let offset = time.to_offset(time_0_3::UtcOffset::from_hms(5, 30, 0).unwrap());
+        assert_eq!(Bson::from(offset), bson);
+
+        assert_eq!(Bson::I64(1_577_836_800_123).to_time_0_3(), None);
+    }
+
+    #[test]
+    fn time_0_3_truncates_to_millis() {
+        let time = time_0_3::OffsetDateTime::from_unix_timestamp_nanos(1_999_999).unwrap();
+        assert_eq!(UtcDateTime::from(time).to_millis(), 1);
+
+        // Times before the epoch round down, like their encoding.
+        let time = time_0_3::OffsetDateTime::from_unix_timestamp_nanos(-1).unwrap();
+        assert_eq!(UtcDateTime::from(time).to_millis(), -1);
+        assert_eq!(
+            UtcDateTime::from(time).to_time_0_3().unwrap(),
+            time_0_3::OffsetDateTime::from_unix_timestamp_nanos(-1_000_000).unwrap()
+        );
+    }
+
+    #[test]
+    fn time_0_3_out_of_range() {
+        // With `large-dates`, time's range is wider than chrono's, so every datetime converts.
+        let convertible = time_0_3::PrimitiveDateTime::MAX.year() > 9999;
+        let max = UtcDateTime::from_millis(i64::MAX).to_time_0_3();
+        let min = UtcDateTime::from_millis(i64::MIN).to_time_0_3();
+        assert_eq!(max.is_some(), convertible);
+        assert_eq!(min.is_some(), convertible);
+    }
+
+    #[test]
+    fn time_0_3_bounds() {
+        let max = time_0_3::PrimitiveDateTime::MAX.assume_utc();
+        let min = time_0_3::PrimitiveDateTime::MIN.assume_utc();
+
+        // Without `large-dates`, every time fits within chrono's range.
+        if max.year() <= 9999 {
+            assert_eq!(UtcDateTime::from(max).year(), max.year());
+            assert_eq!(UtcDateTime::from(min).year(), min.year());
+            return;
+        }
+
+        assert_eq!(*UtcDateTime::from(max), DateTime::<Utc>::MAX_UTC);
+        assert_eq!(*UtcDateTime::from(min), DateTime::<Utc>::MIN_UTC);
+        assert_eq!(Bson::from(max), Bson::UtcDatetime(DateTime::<Utc>::MAX_UTC));
+    }
+}
@@ -132,6 +132,34 @@ impl Decimal128 {
         }
     }
 
+    /// Construct a `Decimal128` from its 16-byte representation, in the little-endian order in
+    /// which it appears in encoded BSON.
+    ///
+    /// ```rust
+    /// use bson::decimal128::Decimal128;
+    ///
+    /// let bytes = Decimal128::from_i32(1).to_bytes();
+    /// let dec128 = Decimal128::from_bytes(bytes);
+    /// assert_eq!(dec128.to_i32(), 1);
+    /// ```
+    pub fn from_bytes(bytes: [u8; 16]) -> Decimal128 {
+        // Every 128-bit pattern is a valid decimal128 encoding.
+        unsafe { Decimal128::from_raw_bytes_le(bytes) }
+    }
+
+    /// Returns the 16-byte representation of the value, in the little-endian order in which it
+    /// appears in encoded BSON.
+    ///
+    /// ```rust
+    /// use bson::decimal128::Decimal128;
+    ///
+    /// let dec128 = Decimal128::from_str("12.70");
+    /// assert_eq!(Decimal128::from_bytes(dec128.to_bytes()), dec128);
+    /// ```
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.to_raw_bytes_le()
+    }
+
     #[doc(hidden)]
     pub unsafe fn from_raw_bytes_le(mut raw: [u8; 16]) -> Decimal128 {
         if cfg!(target_endian = "big") {
@@ -248,6 +276,25 @@ mod test {
         assert!(Decimal128::from_str("NaN").is_nan());
     }
 
+    #[test]
+    fn decimal128_bytes() {
+        let dec = Decimal128::from_str("12.70");
+        let bytes = dec.to_bytes();
+        assert_eq!(Decimal128::from_bytes(bytes), dec);
+
+        // The bytes are exactly those written to the wire.
+        let mut buf = Vec::new();
+        crate::encode_document(
+            &mut buf,
+            &crate::doc! { "d": crate::Bson::Decimal128(dec.clone()) },
+        )
+        .unwrap();
+        assert_eq!(buf[7..23], bytes[..]);
+
+        let dec = Decimal128::from_str("-76");
+        assert_eq!(Decimal128::from_bytes(dec.to_bytes()), dec);
+    }
+
     #[test]
     fn decimal128_i32() {
         let num: i32 = 89;
@@ -0,0 +1,226 @@
+//! [BSON Decimal128](https://github.com/mongodb/specifications/blob/master/source/bson-decimal128/decimal128.rst) data type representation
+//!
+//! Without the `decimal128` feature, values are kept as their encoded bytes, so that documents
+//! containing them can still be decoded, converted to and from extended JSON and encoded again
+//! unchanged. Enable the feature to do arithmetic with them.
+
+use std::{fmt, str::FromStr};
+
+/// Decimal128 type
+#[derive(Clone, PartialEq)]
+pub struct Decimal128 {
+    bytes: [u8; 16],
+}
+
+// The largest coefficient a decimal128 value may have: 34 decimal digits. Encodings with a larger
+// coefficient are non-canonical and denote zero.
+const MAX_COEFFICIENT: u128 = 9_999_999_999_999_999_999_999_999_999_999_999;
+const MAX_DIGITS: usize = 34;
+const EXPONENT_BIAS: i32 = 6176;
+const MIN_EXPONENT: i64 = -6176;
+const MAX_EXPONENT: i64 = 6111;
+const INFINITY: u64 = 0x7800_0000_0000_0000;
+const NAN: u64 = 0x7c00_0000_0000_0000;
+
+impl Decimal128 {
+    /// Constructs a `Decimal128` from its 16-byte representation, in the little-endian order in
+    /// which it appears in encoded BSON.
+    ///
+    /// ```rust
+    /// use bson::decimal128::Decimal128;
+    ///
+    /// let mut bytes = [0; 16];
+    /// bytes[0] = 15;
+    /// bytes[14] = 0x3e;
+    /// bytes[15] = 0x30;
+    /// assert_eq!(Decimal128::from_bytes(bytes).to_string(), "1.5");
+    /// ```
+    pub fn from_bytes(bytes: [u8; 16]) -> Decimal128 {
+        Decimal128 { bytes }
+    }
+
+    /// Returns the 16-byte representation of the value, in the little-endian order in which it
+    /// appears in encoded BSON.
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.bytes
+    }
+
+    /// Check if value is `NaN`
+    pub fn is_nan(&self) -> bool {
+        self.high() >> 58 & 0x1f == 0x1f
+    }
+
+    fn from_parts(negative: bool, high: u64, low: u64) -> Decimal128 {
+        let high = if negative { high | 1 << 63 } else { high };
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&low.to_le_bytes());
+        bytes[8..].copy_from_slice(&high.to_le_bytes());
+        Decimal128 { bytes }
+    }
+
+    fn high(&self) -> u64 {
+        let mut high = [0; 8];
+        high.copy_from_slice(&self.bytes[8..]);
+        u64::from_le_bytes(high)
+    }
+
+    fn low(&self) -> u64 {
+        let mut low = [0; 8];
+        low.copy_from_slice(&self.bytes[..8]);
+        u64::from_le_bytes(low)
+    }
+}
+
+impl fmt::Debug for Decimal128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Decimal(\"{}\")", self)
+    }
+}
+
+/// Formats the value as the specification's canonical string, which is what extended JSON's
+/// `$numberDecimal` holds.
+impl fmt::Display for Decimal128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let high = self.high();
+        let negative = high >> 63 == 1;
+        let combination = high >> 58 & 0x1f;
+
+        if combination == 0x1f {
+            return f.write_str("NaN");
+        }
+
+        if negative {
+            f.write_str("-")?;
+        }
+
+        if combination == 0x1e {
+            return f.write_str("Infinity");
+        }
+
+        let (biased_exponent, coefficient) = if high >> 61 & 0x3 == 0x3 {
+            // The implicit leading bits make the coefficient larger than the maximum.
+            (high >> 47 & 0x3fff, 0)
+        } else {
+            let coefficient = u128::from(high & 0x1_ffff_ffff_ffff) << 64 | u128::from(self.low());
+            (high >> 49 & 0x3fff, coefficient)
+        };
+
+        let coefficient = if coefficient > MAX_COEFFICIENT {
+            0
+        } else {
+            coefficient
+        };
+        let exponent = biased_exponent as i32 - EXPONENT_BIAS;
+        let digits = coefficient.to_string();
+        let adjusted_exponent = exponent + digits.len() as i32 - 1;
+
+        if exponent > 0 || adjusted_exponent < -6 {
+            let (first, rest) = digits.split_at(1);
+            f.write_str(first)?;
+            if !rest.is_empty() {
+                write!(f, ".{}", rest)?;
+            }
+            write!(f, "E{:+}", adjusted_exponent)
+        } else if exponent == 0 {
+            f.write_str(&digits)
+        } else {
+            let radix_position = digits.len() as i32 + exponent;
+            if radix_position > 0 {
+                let (whole, fraction) = digits.split_at(radix_position as usize);
+                write!(f, "{}.{}", whole, fraction)
+            } else {
+                write!(f, "0.{}{}", "0".repeat(-radix_position as usize), digits)
+            }
+        }
+    }
+}
+
+/// Parses the specification's string form, such as `1.05E+3`, `-0.00` or `Infinity`. Values that
+/// cannot be represented exactly are rejected rather than rounded.
+///
+/// ```rust
+/// use bson::decimal128::Decimal128;
+///
+/// let dec128: Decimal128 = "12.70".parse().unwrap();
+/// assert_eq!(dec128.to_string(), "12.70");
+/// ```
+impl FromStr for Decimal128 {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Decimal128, ()> {
+        let (negative, rest) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        if rest.eq_ignore_ascii_case("infinity") || rest.eq_ignore_ascii_case("inf") {
+            return Ok(Decimal128::from_parts(negative, INFINITY, 0));
+        }
+        if rest.eq_ignore_ascii_case("nan") {
+            return Ok(Decimal128::from_parts(false, NAN, 0));
+        }
+
+        let (significand, exponent) = match rest.find(['e', 'E']) {
+            Some(i) => {
+                let exponent = &rest[i + 1..];
+                let digits = exponent.trim_start_matches(['+', '-']);
+                if digits.is_empty() || digits.len() > 6 || exponent.len() - digits.len() > 1 {
+                    return Err(());
+                }
+                (&rest[..i], exponent.parse::<i64>().map_err(|_| ())?)
+            }
+            None => (rest, 0),
+        };
+
+        let (whole, fraction) = match significand.find('.') {
+            Some(i) => (&significand[..i], &significand[i + 1..]),
+            None => (significand, ""),
+        };
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(());
+        }
+
+        let mut digits: Vec<u8> = whole.bytes().chain(fraction.bytes()).collect();
+        let mut exponent = exponent - fraction.len() as i64;
+        let leading_zeros = digits.iter().take_while(|&&b| b == b'0').count();
+        digits.drain(..leading_zeros);
+
+        // Trailing zeros can be dropped, or appended, by moving the exponent, but nothing else
+        // can change without losing precision.
+        while digits.len() > MAX_DIGITS && digits.last() == Some(&b'0') {
+            digits.pop();
+            exponent += 1;
+        }
+        while exponent < MIN_EXPONENT && digits.last() == Some(&b'0') {
+            digits.pop();
+            exponent += 1;
+        }
+        while exponent > MAX_EXPONENT && !digits.is_empty() && digits.len() < MAX_DIGITS {
+            digits.push(b'0');
+            exponent -= 1;
+        }
+        if digits.is_empty() {
+            exponent = exponent.clamp(MIN_EXPONENT, MAX_EXPONENT);
+        }
+        if digits.len() > MAX_DIGITS || !(MIN_EXPONENT..=MAX_EXPONENT).contains(&exponent) {
+            return Err(());
+        }
+
+        let coefficient = digits
+            .iter()
+            .fold(0u128, |acc, &b| acc * 10 + u128::from(b - b'0'));
+        let biased_exponent = (exponent + i64::from(EXPONENT_BIAS)) as u64;
+
+        Ok(Decimal128::from_parts(
+            negative,
+            biased_exponent << 49 | (coefficient >> 64) as u64,
+            coefficient as u64,
+        ))
+    }
+}
@@ -22,25 +22,36 @@
 //! Decoder
 
 mod error;
+mod raw;
+mod raw_document;
 mod serde;
+mod stream;
 
 pub use self::{
     error::{DecoderError, DecoderResult},
+    raw::RawDecoder,
+    raw_document::{RawBson, RawDocument, RawDocumentIter},
     serde::Decoder,
+    stream::DocumentReader,
 };
 
-use std::io::Read;
+use std::io::{Cursor, Read};
 
 use byteorder::{LittleEndian, ReadBytesExt};
-use chrono::{
-    offset::{LocalResult, TimeZone},
-    Utc,
-};
 
-#[cfg(feature = "decimal128")]
-use crate::decimal128::Decimal128;
 use crate::{
-    bson::{Array, Binary, Bson, DbPointer, Document, JavaScriptCodeWithScope, Regex, TimeStamp},
+    bson::{
+        Array,
+        Binary,
+        Bson,
+        DbPointer,
+        Document,
+        JavaScriptCodeWithScope,
+        Regex,
+        TimeStamp,
+        UtcDateTime,
+    },
+    decimal128::Decimal128,
     oid,
     spec::{self, BinarySubtype},
 };
@@ -48,6 +59,8 @@ use crate::{
 use ::serde::de::Deserialize;
 
 const MAX_BSON_SIZE: i32 = 16 * 1024 * 1024;
+// length (4) + empty code string (4 + 1) + empty scope document (5)
+const MIN_CODE_WITH_SCOPE_SIZE: i32 = 14;
 
 fn read_string<R: Read + ?Sized>(reader: &mut R, utf8_lossy: bool) -> DecoderResult<String> {
     let len = reader.read_i32::<LittleEndian>()?;
@@ -60,12 +73,13 @@ fn read_string<R: Read + ?Sized>(reader: &mut R, utf8_lossy: bool) -> DecoderRes
         ));
     }
 
+    // As with binary values, don't size the buffer from the declared length, which may be bogus.
     let s = if utf8_lossy {
-        let mut buf = Vec::with_capacity(len as usize - 1);
+        let mut buf = Vec::new();
         reader.take(len as u64 - 1).read_to_end(&mut buf)?;
         String::from_utf8_lossy(&buf).to_string()
     } else {
-        let mut s = String::with_capacity(len as usize - 1);
+        let mut s = String::new();
         reader.take(len as u64 - 1).read_to_string(&mut s)?;
         s
     };
@@ -98,46 +112,143 @@ fn read_i64<R: Read + ?Sized>(reader: &mut R) -> DecoderResult<i64> {
     reader.read_i64::<LittleEndian>().map_err(From::from)
 }
 
-#[cfg(feature = "decimal128")]
 #[inline]
 fn read_f128<R: Read + ?Sized>(reader: &mut R) -> DecoderResult<Decimal128> {
-    use std::mem;
-
-    let mut local_buf: [u8; 16] = unsafe { mem::MaybeUninit::uninit().assume_init() };
+    let mut local_buf = [0; 16];
     reader.read_exact(&mut local_buf)?;
-    let val = unsafe { Decimal128::from_raw_bytes_le(local_buf) };
-    Ok(val)
+    Ok(Decimal128::from_bytes(local_buf))
 }
 
-/// Attempt to decode a `Document` from a byte stream.
-pub fn decode_document<R: Read + ?Sized>(reader: &mut R) -> DecoderResult<Document> {
-    let mut doc = Document::new();
+/// Tracks how many bytes have been read, so that the length prefixes of documents and arrays can
+/// be checked against what their contents actually consume. The decoding functions below are
+/// written against this one wrapper type, rather than wrapping at every level of nesting, which
+/// would recurse infinitely when instantiating their generic types.
+///
+/// It also carries the largest length a document, array or binary value may declare, which is
+/// checked before anything is read into memory on the strength of that length.
+struct CountingReader<'a, R: Read + ?Sized> {
+    inner: &'a mut R,
+    pos: u64,
+    max_size: u64,
+}
 
-    // disregard the length: using Read::take causes infinite type recursion
-    read_i32(reader)?;
+impl<'a, R: Read + ?Sized> CountingReader<'a, R> {
+    fn new(inner: &'a mut R) -> CountingReader<'a, R> {
+        CountingReader::with_max_size(inner, MAX_BSON_SIZE as usize)
+    }
 
-    loop {
-        let tag = reader.read_u8()?;
+    fn with_max_size(inner: &'a mut R, max_size: usize) -> CountingReader<'a, R> {
+        CountingReader {
+            inner,
+            pos: 0,
+            max_size: max_size as u64,
+        }
+    }
 
-        if tag == 0 {
-            break;
+    // Rejects a declared length greater than the maximum size.
+    fn check_len(&self, len: i32, what: &str) -> DecoderResult<()> {
+        if len as u64 > self.max_size {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!(
+                    "{} length {} exceeds the maximum of {} bytes",
+                    what, len, self.max_size
+                ),
+            ));
         }
 
-        let key = read_cstring(reader)?;
-        let val = decode_bson(reader, tag, false)?;
+        Ok(())
+    }
+}
 
-        doc.insert(key, val);
+impl<R: Read + ?Sized> Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
     }
+}
 
-    Ok(doc)
+/// Attempt to decode a `Document` from a byte stream.
+///
+/// Documents, arrays and binary values declaring a length greater than 16MB, the largest
+/// document MongoDB accepts, are rejected before anything is read on the strength of that
+/// length. Use `decode_document_with_max_size` to change the limit.
+pub fn decode_document<R: Read + ?Sized>(reader: &mut R) -> DecoderResult<Document> {
+    read_document(&mut CountingReader::new(reader), false)
+}
+
+/// Attempt to decode a `Document` from a byte stream, rejecting any document, array or binary
+/// value whose declared length is greater than `max_size` bytes.
+///
+/// ```rust
+/// use std::io::Cursor;
+///
+/// use bson::{decode_document_with_max_size, doc, to_vec, DecoderError};
+///
+/// let bytes = to_vec(&doc! { "x": 1 }).unwrap();
+/// assert!(decode_document_with_max_size(&mut Cursor::new(&bytes), 64).is_ok());
+/// assert!(matches!(
+///     decode_document_with_max_size(&mut Cursor::new(&bytes), 8),
+///     Err(DecoderError::InvalidLength(..))
+/// ));
+/// ```
+pub fn decode_document_with_max_size<R: Read + ?Sized>(
+    reader: &mut R,
+    max_size: usize,
+) -> DecoderResult<Document> {
+    read_document(&mut CountingReader::with_max_size(reader, max_size), false)
 }
 
 /// Attempt to decode a `Document` that may contain invalid UTF-8 strings from a byte stream.
 pub fn decode_document_utf8_lossy<R: Read + ?Sized>(reader: &mut R) -> DecoderResult<Document> {
-    let mut doc = Document::new();
+    read_document(&mut CountingReader::new(reader), true)
+}
+
+// Reads the length prefix of a document or array, returning the position just past its end.
+fn read_document_len<R: Read + ?Sized>(reader: &mut CountingReader<R>) -> DecoderResult<u64> {
+    let start = reader.pos;
+    let len = read_i32(reader)?;
+
+    // A document has at least its length and the trailing 0x00.
+    if len < 5 {
+        return Err(DecoderError::InvalidLength(
+            len as usize,
+            format!("invalid length {} for document", len),
+        ));
+    }
+    reader.check_len(len, "document")?;
+
+    Ok(start + len as u64)
+}
+
+// Checks that the null byte just read terminated the document or array spanning `start..end`.
+fn end_document<R: Read + ?Sized>(
+    reader: &CountingReader<R>,
+    start: u64,
+    end: u64,
+) -> DecoderResult<()> {
+    if reader.pos != end {
+        return Err(DecoderError::InvalidLength(
+            (end - start) as usize,
+            format!(
+                "document length {} does not match the {} bytes read",
+                end - start,
+                reader.pos - start
+            ),
+        ));
+    }
 
-    // disregard the length: using Read::take causes infinite type recursion
-    read_i32(reader)?;
+    Ok(())
+}
+
+fn read_document<R: Read + ?Sized>(
+    reader: &mut CountingReader<R>,
+    utf8_lossy: bool,
+) -> DecoderResult<Document> {
+    let mut doc = Document::new();
+    let start = reader.pos;
+    let end = read_document_len(reader)?;
 
     loop {
         let tag = reader.read_u8()?;
@@ -147,19 +258,22 @@ pub fn decode_document_utf8_lossy<R: Read + ?Sized>(reader: &mut R) -> DecoderRe
         }
 
         let key = read_cstring(reader)?;
-        let val = decode_bson(reader, tag, true)?;
+        let val = read_bson(reader, tag, utf8_lossy)?;
 
         doc.insert(key, val);
     }
 
+    end_document(reader, start, end)?;
     Ok(doc)
 }
 
-fn decode_array<R: Read + ?Sized>(reader: &mut R, utf8_lossy: bool) -> DecoderResult<Array> {
+fn read_array<R: Read + ?Sized>(
+    reader: &mut CountingReader<R>,
+    utf8_lossy: bool,
+) -> DecoderResult<Array> {
     let mut arr = Array::new();
-
-    // disregard the length: using Read::take causes infinite type recursion
-    read_i32(reader)?;
+    let start = reader.pos;
+    let end = read_document_len(reader)?;
 
     loop {
         let tag = reader.read_u8()?;
@@ -178,14 +292,23 @@ fn decode_array<R: Read + ?Sized>(reader: &mut R, utf8_lossy: bool) -> DecoderRe
             }
         }
 
-        let val = decode_bson(reader, tag, utf8_lossy)?;
+        let val = read_bson(reader, tag, utf8_lossy)?;
         arr.push(val)
     }
 
+    end_document(reader, start, end)?;
     Ok(arr)
 }
 
 fn decode_bson<R: Read + ?Sized>(reader: &mut R, tag: u8, utf8_lossy: bool) -> DecoderResult<Bson> {
+    read_bson(&mut CountingReader::new(reader), tag, utf8_lossy)
+}
+
+fn read_bson<R: Read + ?Sized>(
+    reader: &mut CountingReader<R>,
+    tag: u8,
+    utf8_lossy: bool,
+) -> DecoderResult<Bson> {
     use spec::ElementType;
 
     match ElementType::from(tag) {
@@ -193,19 +316,35 @@ fn decode_bson<R: Read + ?Sized>(reader: &mut R, tag: u8, utf8_lossy: bool) -> D
             Ok(Bson::FloatingPoint(reader.read_f64::<LittleEndian>()?))
         }
         Some(ElementType::Utf8String) => read_string(reader, utf8_lossy).map(Bson::String),
-        Some(ElementType::EmbeddedDocument) => decode_document(reader).map(Bson::Document),
-        Some(ElementType::Array) => decode_array(reader, utf8_lossy).map(Bson::Array),
+        Some(ElementType::EmbeddedDocument) => {
+            read_document(reader, utf8_lossy).map(Bson::Document)
+        }
+        Some(ElementType::Array) => read_array(reader, utf8_lossy).map(Bson::Array),
         Some(ElementType::Binary) => {
             let len = read_i32(reader)?;
-            if len < 0 || len > MAX_BSON_SIZE {
+            if len < 0 {
                 return Err(DecoderError::InvalidLength(
                     len as usize,
                     format!("Invalid binary length of {}", len),
                 ));
             }
+            reader.check_len(len, "binary")?;
             let subtype = BinarySubtype::from(reader.read_u8()?);
-            let mut bytes = Vec::with_capacity(len as usize);
+
+            // Let the buffer grow with the bytes actually read rather than trusting the declared
+            // length up front, so a bogus length on a short input can't force a large allocation.
+            let mut bytes = Vec::new();
             reader.take(len as u64).read_to_end(&mut bytes)?;
+            if bytes.len() != len as usize {
+                return Err(DecoderError::InvalidLength(
+                    len as usize,
+                    format!(
+                        "binary length {} exceeds the {} bytes remaining",
+                        len,
+                        bytes.len()
+                    ),
+                ));
+            }
             Ok(Bson::Binary(Binary { subtype, bytes }))
         }
         Some(ElementType::ObjectId) => {
@@ -226,12 +365,51 @@ fn decode_bson<R: Read + ?Sized>(reader: &mut R, tag: u8, utf8_lossy: bool) -> D
             read_string(reader, utf8_lossy).map(Bson::JavaScriptCode)
         }
         Some(ElementType::JavaScriptCodeWithScope) => {
-            // disregard the length:
-            //     using Read::take causes infinite type recursion
-            read_i32(reader)?;
+            // The length covers itself, the code string and the scope document.
+            let len = read_i32(reader)?;
+            if len < MIN_CODE_WITH_SCOPE_SIZE {
+                return Err(DecoderError::InvalidLength(
+                    len as usize,
+                    format!("Invalid code with scope length of {}", len),
+                ));
+            }
+            reader.check_len(len, "code with scope")?;
+
+            // Read the whole value up front so the declared length can be checked against what
+            // the code and scope actually consume. As with binary values, let the buffer grow with
+            // the bytes actually read.
+            let mut buf = Vec::new();
+            reader.take(len as u64 - 4).read_to_end(&mut buf)?;
+            if buf.len() != len as usize - 4 {
+                return Err(DecoderError::InvalidLength(
+                    len as usize,
+                    format!(
+                        "code with scope length {} exceeds the {} bytes remaining",
+                        len,
+                        buf.len() + 4
+                    ),
+                ));
+            }
+
+            let mut value = Cursor::new(&buf[..]);
+            let code = read_string(&mut value, utf8_lossy)?;
+            let max_size = reader.max_size as usize;
+            let scope = read_document(
+                &mut CountingReader::with_max_size(&mut value, max_size),
+                utf8_lossy,
+            )?;
+
+            if value.position() != buf.len() as u64 {
+                return Err(DecoderError::InvalidLength(
+                    len as usize,
+                    format!(
+                        "code with scope length {} does not match its contents of {} bytes",
+                        len,
+                        value.position() + 4
+                    ),
+                ));
+            }
 
-            let code = read_string(reader, utf8_lossy)?;
-            let scope = decode_document(reader)?;
             Ok(Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
                 code,
                 scope,
@@ -246,22 +424,11 @@ fn decode_bson<R: Read + ?Sized>(reader: &mut R, tag: u8, utf8_lossy: bool) -> D
             // The int64 is UTC milliseconds since the Unix epoch.
             let time = read_i64(reader)?;
 
-            let sec = time / 1000;
-            let tmp_msec = time % 1000;
-            let msec = if tmp_msec < 0 {
-                1000 - tmp_msec
-            } else {
-                tmp_msec
-            };
-
-            match Utc.timestamp_opt(sec, (msec as u32) * 1_000_000) {
-                LocalResult::None => Err(DecoderError::InvalidTimestamp(time)),
-                LocalResult::Ambiguous(..) => Err(DecoderError::AmbiguousTimestamp(time)),
-                LocalResult::Single(t) => Ok(Bson::UtcDatetime(t)),
-            }
+            UtcDateTime::try_from_millis(time)
+                .map(|date| Bson::UtcDatetime(date.0))
+                .ok_or(DecoderError::InvalidTimestamp(time))
         }
         Some(ElementType::Symbol) => read_string(reader, utf8_lossy).map(Bson::Symbol),
-        #[cfg(feature = "decimal128")]
         Some(ElementType::Decimal128Bit) => read_f128(reader).map(Bson::Decimal128),
         Some(ElementType::Undefined) => Ok(Bson::Undefined),
         Some(ElementType::DbPointer) => {
@@ -287,3 +454,39 @@ where
     let de = Decoder::new(bson);
     Deserialize::deserialize(de)
 }
+
+/// Decode a single BSON document at the start of `bytes` into a `T` Deserializable, returning it
+/// along with the number of bytes consumed.
+///
+/// Any bytes after the document are left untouched, so the count can be used to advance through a
+/// buffer of back-to-back documents.
+///
+/// Lengths are limited to 16MB as with `RawDecoder::new`; deserialize from a decoder built with
+/// `RawDecoder::with_max_size` to use a different limit.
+///
+/// ```rust
+/// use bson::{doc, encode_document, from_slice, Document};
+///
+/// let mut bytes = Vec::new();
+/// encode_document(&mut bytes, &doc! { "x": 1 }).unwrap();
+/// encode_document(&mut bytes, &doc! { "y": 2 }).unwrap();
+///
+/// let (first, read): (Document, usize) = from_slice(&bytes).unwrap();
+/// let (second, _): (Document, usize) = from_slice(&bytes[read..]).unwrap();
+/// assert_eq!(first, doc! { "x": 1 });
+/// assert_eq!(second, doc! { "y": 2 });
+/// ```
+pub fn from_slice<'de, T>(bytes: &'de [u8]) -> DecoderResult<(T, usize)>
+where
+    T: Deserialize<'de>,
+{
+    let mut de = RawDecoder::new(bytes);
+    let value = Deserialize::deserialize(&mut de)?;
+    Ok((value, de.bytes_read()))
+}
+
+/// Decode a `Document` from a byte stream and convert it to extended JSON, as produced by
+/// `serde_json::Value::from`.
+pub fn from_reader_to_json<R: Read>(mut reader: R) -> DecoderResult<serde_json::Value> {
+    decode_document(&mut reader).map(|doc| Bson::Document(doc).into())
+}
@@ -0,0 +1,756 @@
+use std::str;
+
+use ::serde::de::{
+    value::{BorrowedBytesDeserializer, BorrowedStrDeserializer, SeqDeserializer},
+    Deserialize,
+    DeserializeSeed,
+    Deserializer,
+    EnumAccess,
+    IgnoredAny,
+    MapAccess,
+    SeqAccess,
+    VariantAccess,
+    Visitor,
+};
+use byteorder::{ByteOrder, LittleEndian};
+
+use super::{
+    read_bson,
+    serde::Decoder,
+    CountingReader,
+    DecoderError,
+    DecoderResult,
+    MAX_BSON_SIZE,
+    MIN_CODE_WITH_SCOPE_SIZE,
+};
+use crate::{
+    bson::{Binary, Bson},
+    encoder::{BSON_NEWTYPE, DB_POINTER_NEWTYPE, UNDEFINED_UNIT},
+    spec::{BinarySubtype, ElementType},
+};
+
+/// Serde Decoder that reads BSON directly from a byte slice.
+///
+/// Unlike `Decoder`, which walks an already-decoded `Bson` value, `RawDecoder` deserializes
+/// straight from the encoded bytes. Strings, keys and generic binary data are handed to the
+/// visitor as slices of the input, so types that borrow (such as `&'de str` or `&'de [u8]`) can
+/// be deserialized without copying. Those borrows are tied to the `'de` lifetime of the input
+/// slice, which must therefore outlive the deserialized value.
+///
+/// ```rust
+/// use bson::{doc, encode_document, RawDecoder};
+/// use serde::Deserialize;
+///
+/// let mut bytes = Vec::new();
+/// encode_document(&mut bytes, &doc! { "name": "bson" }).unwrap();
+///
+/// let mut decoder = RawDecoder::new(&bytes);
+/// let doc = bson::Document::deserialize(&mut decoder).unwrap();
+/// assert_eq!(doc, doc! { "name": "bson" });
+/// ```
+pub struct RawDecoder<'de> {
+    bytes: &'de [u8],
+    pub(super) pos: usize,
+    pub(super) current_type: ElementType,
+    max_size: usize,
+}
+
+impl<'de> RawDecoder<'de> {
+    /// Constructs a decoder for the document at the start of `bytes`.
+    ///
+    /// Documents, arrays and binary values declaring a length greater than 16MB, the largest
+    /// document MongoDB accepts, are rejected. Use `RawDecoder::with_max_size` to change the
+    /// limit.
+    pub fn new(bytes: &'de [u8]) -> RawDecoder<'de> {
+        RawDecoder::with_max_size(bytes, MAX_BSON_SIZE as usize)
+    }
+
+    /// Constructs a decoder for the document at the start of `bytes`, rejecting any document,
+    /// array or binary value whose declared length is greater than `max_size` bytes.
+    pub fn with_max_size(bytes: &'de [u8], max_size: usize) -> RawDecoder<'de> {
+        RawDecoder {
+            bytes,
+            pos: 0,
+            current_type: ElementType::EmbeddedDocument,
+            max_size,
+        }
+    }
+
+    /// Constructs a decoder for the array at the start of `bytes`, encoded like a document whose
+    /// keys are the indexes `"0"`, `"1"`, and so on. This lets a bare BSON array be deserialized
+    /// into a sequence such as a `Vec`.
+    ///
+    /// ```rust
+    /// use bson::{doc, encode_document, RawDecoder};
+    /// use serde::Deserialize;
+    ///
+    /// let mut bytes = Vec::new();
+    /// encode_document(&mut bytes, &doc! { "0": "a", "1": "b" }).unwrap();
+    ///
+    /// let mut decoder = RawDecoder::for_array(&bytes);
+    /// let vec = Vec::<String>::deserialize(&mut decoder).unwrap();
+    /// assert_eq!(vec, ["a", "b"]);
+    /// ```
+    pub fn for_array(bytes: &'de [u8]) -> RawDecoder<'de> {
+        RawDecoder {
+            current_type: ElementType::Array,
+            ..RawDecoder::new(bytes)
+        }
+    }
+
+    /// The number of bytes of the input consumed so far. After a successful deserialize this is
+    /// the encoded length of the document, so any further payload starts at this offset.
+    pub fn bytes_read(&self) -> usize {
+        self.pos
+    }
+
+    fn read_slice(&mut self, len: usize) -> DecoderResult<&'de [u8]> {
+        let end = match self.pos.checked_add(len) {
+            Some(end) if end <= self.bytes.len() => end,
+            _ => return Err(DecoderError::EndOfStream),
+        };
+
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    pub(super) fn read_u8(&mut self) -> DecoderResult<u8> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    fn read_i32(&mut self) -> DecoderResult<i32> {
+        Ok(LittleEndian::read_i32(self.read_slice(4)?))
+    }
+
+    fn read_i64(&mut self) -> DecoderResult<i64> {
+        Ok(LittleEndian::read_i64(self.read_slice(8)?))
+    }
+
+    fn read_f64(&mut self) -> DecoderResult<f64> {
+        Ok(LittleEndian::read_f64(self.read_slice(8)?))
+    }
+
+    pub(super) fn read_cstr(&mut self) -> DecoderResult<&'de str> {
+        let len = self.bytes[self.pos..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(DecoderError::EndOfStream)?;
+
+        let s = self.read_slice(len)?;
+        self.pos += 1; // The last 0x00
+        to_str(s)
+    }
+
+    fn read_str(&mut self) -> DecoderResult<&'de str> {
+        let len = self.read_i32()?;
+
+        // UTF-8 String must have at least 1 byte (the last 0x00).
+        if len < 1 {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!("invalid length {} for UTF-8 string", len),
+            ));
+        }
+
+        let s = self.read_slice(len as usize)?;
+        if s[s.len() - 1] != 0 {
+            return Err(DecoderError::SyntaxError(
+                "string is not null-terminated".to_owned(),
+            ));
+        }
+        to_str(&s[..s.len() - 1])
+    }
+
+    fn read_binary(&mut self) -> DecoderResult<(BinarySubtype, &'de [u8])> {
+        let len = self.read_i32()?;
+        if len < 0 {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!("Invalid binary length of {}", len),
+            ));
+        }
+
+        self.check_len(len, "binary")?;
+
+        let subtype = BinarySubtype::from(self.read_u8()?);
+        Ok((subtype, self.read_slice(len as usize)?))
+    }
+
+    // Rejects a declared length greater than the maximum size.
+    fn check_len(&self, len: i32, what: &str) -> DecoderResult<()> {
+        if len as usize > self.max_size {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!(
+                    "{} length {} exceeds the maximum of {} bytes",
+                    what, len, self.max_size
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the length prefix of a document or array and checks that the declared length fits
+    /// in the input and ends with a null byte, returning the offset just past the end.
+    pub(super) fn read_document_len(&mut self) -> DecoderResult<usize> {
+        let start = self.pos;
+        let len = self.read_i32()?;
+
+        // A document has at least its length and the trailing 0x00.
+        if len < 5 {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!("invalid length {} for document", len),
+            ));
+        }
+        self.check_len(len, "document")?;
+
+        let end = start + len as usize;
+        if end > self.bytes.len() {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!(
+                    "document length {} exceeds the {} bytes available",
+                    len,
+                    self.bytes.len() - start
+                ),
+            ));
+        }
+
+        if self.bytes[end - 1] != 0 {
+            return Err(DecoderError::SyntaxError(
+                "document is not null-terminated".to_owned(),
+            ));
+        }
+
+        Ok(end)
+    }
+
+    /// Checks that the null byte just read terminated the document spanning `start..end`.
+    fn end_document(&self, start: usize, end: usize) -> DecoderResult<()> {
+        if self.pos != end {
+            return Err(DecoderError::InvalidLength(
+                end - start,
+                format!(
+                    "document length {} does not match the {} bytes read",
+                    end - start,
+                    self.pos - start
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Advances past the current element without decoding it. Only the lengths needed to find
+    /// the end of the element are read and checked.
+    pub(super) fn skip_current(&mut self) -> DecoderResult<()> {
+        let len = match self.current_type {
+            ElementType::NullValue
+            | ElementType::Undefined
+            | ElementType::MinKey
+            | ElementType::MaxKey => 0,
+            ElementType::Boolean => 1,
+            ElementType::Integer32Bit => 4,
+            ElementType::FloatingPoint
+            | ElementType::Integer64Bit
+            | ElementType::UtcDatetime
+            | ElementType::TimeStamp => 8,
+            ElementType::ObjectId => 12,
+            ElementType::Decimal128Bit => 16,
+            ElementType::Utf8String | ElementType::JavaScriptCode | ElementType::Symbol => {
+                self.skip_str()?;
+                0
+            }
+            ElementType::DbPointer => {
+                self.skip_str()?;
+                12
+            }
+            ElementType::RegularExpression => {
+                self.read_cstr()?;
+                self.read_cstr()?;
+                0
+            }
+            ElementType::Binary => {
+                self.read_binary()?;
+                0
+            }
+            ElementType::EmbeddedDocument | ElementType::Array => {
+                self.pos = self.read_document_len()?;
+                0
+            }
+            ElementType::JavaScriptCodeWithScope => {
+                let len = self.read_i32()?;
+                if len < MIN_CODE_WITH_SCOPE_SIZE {
+                    return Err(DecoderError::InvalidLength(
+                        len as usize,
+                        format!("invalid length {} for code with scope", len),
+                    ));
+                }
+                self.check_len(len, "code with scope")?;
+                len as usize - 4
+            }
+        };
+
+        self.read_slice(len)?;
+        Ok(())
+    }
+
+    fn skip_str(&mut self) -> DecoderResult<()> {
+        let len = self.read_i32()?;
+        if len < 1 {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!("invalid length {} for UTF-8 string", len),
+            ));
+        }
+
+        self.read_slice(len as usize)?;
+        Ok(())
+    }
+
+    /// Decodes the current element into an owned `Bson` using the `decode_document` machinery.
+    fn decode_current(&mut self) -> DecoderResult<Bson> {
+        let mut rest = &self.bytes[self.pos..];
+        let available = rest.len();
+        let mut reader = CountingReader::with_max_size(&mut rest, self.max_size);
+        let bson = read_bson(&mut reader, self.current_type as u8, false)?;
+        self.pos += available - rest.len();
+        Ok(bson)
+    }
+}
+
+fn to_str(bytes: &[u8]) -> DecoderResult<&str> {
+    // Only pay for the owned conversion when reporting an error, to keep the error type
+    // consistent with `decode_document`.
+    str::from_utf8(bytes)
+        .map_err(|_| DecoderError::FromUtf8Error(String::from_utf8(bytes.to_vec()).unwrap_err()))
+}
+
+impl<'de> Deserializer<'de> for &mut RawDecoder<'de> {
+    type Error = DecoderError;
+
+    fn deserialize_any<V>(self, visitor: V) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.current_type {
+            ElementType::FloatingPoint => visitor.visit_f64(self.read_f64()?),
+            ElementType::Utf8String => visitor.visit_borrowed_str(self.read_str()?),
+            ElementType::EmbeddedDocument => {
+                let start = self.pos;
+                let end = self.read_document_len()?;
+                visitor.visit_map(DocumentAccess {
+                    de: self,
+                    start,
+                    end,
+                })
+            }
+            ElementType::Array => {
+                let start = self.pos;
+                let end = self.read_document_len()?;
+                let mut access = ArrayAccess {
+                    de: self,
+                    start,
+                    end,
+                    len: 0,
+                };
+                let value = visitor.visit_seq(&mut access)?;
+                access.end()?;
+                Ok(value)
+            }
+            ElementType::Binary => match self.read_binary()? {
+                (BinarySubtype::Generic, bytes) => visitor.visit_borrowed_bytes(bytes),
+                (subtype, bytes) => Decoder::new(Bson::Binary(Binary {
+                    subtype,
+                    bytes: bytes.to_vec(),
+                }))
+                .deserialize_any(visitor),
+            },
+            ElementType::Boolean => visitor.visit_bool(self.read_u8()? != 0),
+            ElementType::NullValue => visitor.visit_unit(),
+            ElementType::Integer32Bit => visitor.visit_i32(self.read_i32()?),
+            ElementType::Integer64Bit => visitor.visit_i64(self.read_i64()?),
+            // The remaining types are represented as extended documents, so decode them as
+            // `Bson` and let `Decoder` present them exactly as `from_bson` would.
+            _ => Decoder::new(self.decode_current()?).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.current_type != ElementType::Binary {
+            return self.deserialize_any(visitor);
+        }
+
+        match self.read_binary()? {
+            // Generic binary can fill fixed-size byte arrays.
+            (BinarySubtype::Generic, bytes) => {
+                let mut seq = SeqDeserializer::<_, DecoderError>::new(bytes.iter().copied());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            (subtype, bytes) => Decoder::new(Bson::Binary(Binary {
+                subtype,
+                bytes: bytes.to_vec(),
+            }))
+            .deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.current_type {
+            ElementType::NullValue | ElementType::Undefined => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name != BSON_NEWTYPE {
+            return visitor.visit_newtype_struct(self);
+        }
+
+        // `Bson` can hold these types natively, so read them straight from the input instead of
+        // going through their extended JSON documents.
+        match self.current_type {
+            ElementType::Undefined => visitor.visit_enum(NativeAccess::Undefined),
+            ElementType::DbPointer => {
+                let namespace = self.read_str()?;
+                let id = self.read_slice(12)?;
+                visitor.visit_enum(NativeAccess::DbPointer(namespace, id))
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.current_type {
+            // unit variants are encoded as just the variant name
+            ElementType::Utf8String => {
+                visitor.visit_enum(BorrowedStrDeserializer::new(self.read_str()?))
+            }
+            // other variants are encoded as documents with a single key:value pair
+            ElementType::EmbeddedDocument => {
+                let start = self.pos;
+                let end = self.read_document_len()?;
+
+                let tag = self.read_u8()?;
+                if tag == 0 {
+                    return Err(DecoderError::SyntaxError(
+                        "expected a variant name".to_owned(),
+                    ));
+                }
+                self.current_type =
+                    ElementType::from(tag).ok_or(DecoderError::UnrecognizedElementType(tag))?;
+                let variant = self.read_cstr()?;
+
+                visitor.visit_enum(VariantDocumentAccess {
+                    de: self,
+                    start,
+                    end,
+                    variant,
+                })
+            }
+            _ => Err(DecoderError::InvalidType("expected an enum".to_owned())),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.skip_current()?;
+        visitor.visit_unit()
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq
+        tuple_struct map struct identifier
+    }
+}
+
+struct DocumentAccess<'a, 'de> {
+    de: &'a mut RawDecoder<'de>,
+    start: usize,
+    end: usize,
+}
+
+impl<'de> MapAccess<'de> for DocumentAccess<'_, 'de> {
+    type Error = DecoderError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> DecoderResult<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let tag = self.de.read_u8()?;
+        if tag == 0 {
+            self.de.end_document(self.start, self.end)?;
+            return Ok(None);
+        }
+
+        self.de.current_type =
+            ElementType::from(tag).ok_or(DecoderError::UnrecognizedElementType(tag))?;
+        let key = self.de.read_cstr()?;
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> DecoderResult<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+struct ArrayAccess<'a, 'de> {
+    de: &'a mut RawDecoder<'de>,
+    start: usize,
+    end: usize,
+    len: usize,
+}
+
+impl ArrayAccess<'_, '_> {
+    /// Checks that the visitor consumed every element, as fixed-length visitors such as tuples
+    /// stop without reading the terminating null byte.
+    fn end(&mut self) -> DecoderResult<()> {
+        if self.de.pos == self.end {
+            return Ok(());
+        }
+
+        if self.de.read_u8()? != 0 {
+            return Err(DecoderError::InvalidLength(
+                self.len,
+                format!("array has more than the {} elements expected", self.len),
+            ));
+        }
+
+        self.de.end_document(self.start, self.end)
+    }
+}
+
+impl<'de> SeqAccess<'de> for ArrayAccess<'_, 'de> {
+    type Error = DecoderError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> DecoderResult<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let tag = self.de.read_u8()?;
+        if tag == 0 {
+            self.de.end_document(self.start, self.end)?;
+            return Ok(None);
+        }
+
+        self.de.current_type =
+            ElementType::from(tag).ok_or(DecoderError::UnrecognizedElementType(tag))?;
+
+        // check that the key is as expected
+        let key = self.de.read_cstr()?;
+        if key.parse::<usize>().ok() != Some(self.len) {
+            return Err(DecoderError::InvalidArrayKey(self.len, key.to_owned()));
+        }
+        self.len += 1;
+
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+struct VariantDocumentAccess<'a, 'de> {
+    de: &'a mut RawDecoder<'de>,
+    start: usize,
+    end: usize,
+    variant: &'de str,
+}
+
+impl VariantDocumentAccess<'_, '_> {
+    /// Checks that the variant's value was the only field in the enclosing document.
+    fn end(self) -> DecoderResult<()> {
+        if self.de.read_u8()? != 0 {
+            return Err(DecoderError::InvalidType(
+                "expected a single key:value pair".to_owned(),
+            ));
+        }
+
+        self.de.end_document(self.start, self.end)
+    }
+}
+
+impl<'a, 'de> EnumAccess<'de> for VariantDocumentAccess<'a, 'de> {
+    type Error = DecoderError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> DecoderResult<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant =
+            seed.deserialize(BorrowedStrDeserializer::<DecoderError>::new(self.variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDocumentAccess<'_, 'de> {
+    type Error = DecoderError;
+
+    fn unit_variant(self) -> DecoderResult<()> {
+        IgnoredAny::deserialize(&mut *self.de)?;
+        self.end()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> DecoderResult<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(&mut *self.de)?;
+        self.end()?;
+        Ok(value)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let value = (&mut *self.de).deserialize_seq(visitor)?;
+        self.end()?;
+        Ok(value)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let value = (&mut *self.de).deserialize_map(visitor)?;
+        self.end()?;
+        Ok(value)
+    }
+}
+
+/// An undefined or DBPointer value handed to `Bson`'s visitor as the enum variant named after the
+/// marker `Encoder` uses for it.
+enum NativeAccess<'de> {
+    Undefined,
+    DbPointer(&'de str, &'de [u8]),
+}
+
+impl NativeAccess<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            NativeAccess::Undefined => UNDEFINED_UNIT,
+            NativeAccess::DbPointer(..) => DB_POINTER_NEWTYPE,
+        }
+    }
+
+    fn invalid_variant(&self, expected: &str) -> DecoderError {
+        DecoderError::InvalidType(format!("expected {}, found {}", expected, self.name()))
+    }
+}
+
+impl<'de> EnumAccess<'de> for NativeAccess<'de> {
+    type Error = DecoderError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> DecoderResult<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant =
+            seed.deserialize(BorrowedStrDeserializer::<DecoderError>::new(self.name()))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for NativeAccess<'de> {
+    type Error = DecoderError;
+
+    fn unit_variant(self) -> DecoderResult<()> {
+        match self {
+            NativeAccess::Undefined => Ok(()),
+            _ => Err(self.invalid_variant("a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> DecoderResult<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        Err(self.invalid_variant("a newtype variant"))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            NativeAccess::DbPointer(namespace, id) => visitor.visit_seq(DbPointerAccess {
+                namespace: Some(namespace),
+                id: Some(id),
+            }),
+            _ => Err(self.invalid_variant("a tuple variant")),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(self.invalid_variant("a struct variant"))
+    }
+}
+
+/// The namespace and id of a DBPointer, as the elements of its tuple variant.
+struct DbPointerAccess<'de> {
+    namespace: Option<&'de str>,
+    id: Option<&'de [u8]>,
+}
+
+impl<'de> SeqAccess<'de> for DbPointerAccess<'de> {
+    type Error = DecoderError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> DecoderResult<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if let Some(namespace) = self.namespace.take() {
+            seed.deserialize(BorrowedStrDeserializer::new(namespace))
+                .map(Some)
+        } else if let Some(id) = self.id.take() {
+            seed.deserialize(BorrowedBytesDeserializer::new(id))
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
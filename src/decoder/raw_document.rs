@@ -0,0 +1,232 @@
+use std::str;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use super::{decode_bson, decode_document, raw::RawDecoder, DecoderError, DecoderResult};
+use crate::{
+    bson::{Bson, Document},
+    oid::ObjectId,
+    spec::ElementType,
+};
+
+/// A BSON document borrowed from an encoded buffer, whose fields are located on demand.
+///
+/// Looking up a field only walks the element headers that precede it, skipping over the values
+/// without decoding them, so reading a few fields of a large document avoids building the whole
+/// `Document` tree.
+///
+/// ```rust
+/// use bson::{doc, to_vec, RawDocument};
+///
+/// let bytes = to_vec(&doc! { "name": "bson", "tags": ["a", "b"], "stars": 10 }).unwrap();
+/// let doc = RawDocument::new(&bytes).unwrap();
+///
+/// assert_eq!(doc.get("stars").unwrap().and_then(|v| v.as_i32()), Some(10));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawDocument<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> RawDocument<'a> {
+    /// Wraps the document at the start of `bytes`. Only the length prefix and the trailing null
+    /// byte are checked here; the elements are validated as they are visited.
+    pub fn new(bytes: &'a [u8]) -> DecoderResult<RawDocument<'a>> {
+        let end = RawDecoder::new(bytes).read_document_len()?;
+        Ok(RawDocument {
+            bytes: &bytes[..end],
+        })
+    }
+
+    /// Returns the encoded bytes of the document.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the value for `key`, or `None` if the document has no such field. Returns an
+    /// error if the elements before it are malformed.
+    pub fn get(&self, key: &str) -> DecoderResult<Option<RawBson<'a>>> {
+        for element in self.iter() {
+            let (k, value) = element?;
+            if k == key {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Iterates over the fields of the document in order.
+    pub fn iter(&self) -> RawDocumentIter<'a> {
+        let mut de = RawDecoder::new(self.bytes);
+        // skip the length prefix, which was checked in `new`
+        de.pos = 4;
+        RawDocumentIter {
+            bytes: self.bytes,
+            de,
+            done: false,
+        }
+    }
+
+    /// Iterates over the keys of the document in order.
+    pub fn keys(&self) -> impl Iterator<Item = DecoderResult<&'a str>> {
+        self.iter().map(|element| element.map(|(key, _)| key))
+    }
+
+    /// Decodes the whole document.
+    pub fn to_document(&self) -> DecoderResult<Document> {
+        decode_document(&mut &self.bytes[..])
+    }
+}
+
+/// An iterator over the fields of a `RawDocument`.
+///
+/// Iteration stops after the first error.
+pub struct RawDocumentIter<'a> {
+    bytes: &'a [u8],
+    de: RawDecoder<'a>,
+    done: bool,
+}
+
+impl<'a> RawDocumentIter<'a> {
+    fn read_element(&mut self) -> DecoderResult<Option<(&'a str, RawBson<'a>)>> {
+        let tag = self.de.read_u8()?;
+        if tag == 0 {
+            if self.de.pos != self.bytes.len() {
+                return Err(DecoderError::InvalidLength(
+                    self.bytes.len(),
+                    format!(
+                        "document length {} does not match the {} bytes read",
+                        self.bytes.len(),
+                        self.de.pos
+                    ),
+                ));
+            }
+            return Ok(None);
+        }
+
+        let element_type =
+            ElementType::from(tag).ok_or(DecoderError::UnrecognizedElementType(tag))?;
+        self.de.current_type = element_type;
+        let key = self.de.read_cstr()?;
+
+        let start = self.de.pos;
+        self.de.skip_current()?;
+        let value = RawBson {
+            element_type,
+            bytes: &self.bytes[start..self.de.pos],
+        };
+
+        Ok(Some((key, value)))
+    }
+}
+
+impl<'a> Iterator for RawDocumentIter<'a> {
+    type Item = DecoderResult<(&'a str, RawBson<'a>)>;
+
+    fn next(&mut self) -> Option<DecoderResult<(&'a str, RawBson<'a>)>> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_element().transpose();
+        if let None | Some(Err(_)) = result {
+            self.done = true;
+        }
+        result
+    }
+}
+
+/// A single value borrowed from a `RawDocument`, left encoded until it is accessed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawBson<'a> {
+    element_type: ElementType,
+    bytes: &'a [u8],
+}
+
+impl<'a> RawBson<'a> {
+    /// The type of the value.
+    pub fn element_type(&self) -> ElementType {
+        self.element_type
+    }
+
+    /// Returns the encoded bytes of the value, without its type tag or key.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// If the value is a double, return it. Returns `None` otherwise.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.element_type {
+            ElementType::FloatingPoint => Some(LittleEndian::read_f64(self.bytes)),
+            _ => None,
+        }
+    }
+
+    /// If the value is a valid UTF-8 string, return it. Returns `None` otherwise.
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.element_type {
+            // skip the length prefix and the trailing null byte
+            ElementType::Utf8String => str::from_utf8(&self.bytes[4..self.bytes.len() - 1]).ok(),
+            _ => None,
+        }
+    }
+
+    /// If the value is an embedded document, return it. Returns `None` otherwise.
+    pub fn as_document(&self) -> Option<RawDocument<'a>> {
+        match self.element_type {
+            ElementType::EmbeddedDocument => Some(RawDocument { bytes: self.bytes }),
+            _ => None,
+        }
+    }
+
+    /// If the value is an array, return it as a document keyed by index. Returns `None`
+    /// otherwise.
+    pub fn as_array(&self) -> Option<RawDocument<'a>> {
+        match self.element_type {
+            ElementType::Array => Some(RawDocument { bytes: self.bytes }),
+            _ => None,
+        }
+    }
+
+    /// If the value is a boolean, return it. Returns `None` otherwise.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.element_type {
+            ElementType::Boolean => Some(self.bytes[0] != 0),
+            _ => None,
+        }
+    }
+
+    /// If the value is an i32, return it. Returns `None` otherwise.
+    pub fn as_i32(&self) -> Option<i32> {
+        match self.element_type {
+            ElementType::Integer32Bit => Some(LittleEndian::read_i32(self.bytes)),
+            _ => None,
+        }
+    }
+
+    /// If the value is an i64, return it. Returns `None` otherwise.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.element_type {
+            ElementType::Integer64Bit => Some(LittleEndian::read_i64(self.bytes)),
+            _ => None,
+        }
+    }
+
+    /// If the value is an ObjectId, return it. Returns `None` otherwise.
+    pub fn as_object_id(&self) -> Option<ObjectId> {
+        match self.element_type {
+            ElementType::ObjectId => {
+                let mut id = [0; 12];
+                id.copy_from_slice(self.bytes);
+                Some(ObjectId::with_bytes(id))
+            }
+            _ => None,
+        }
+    }
+
+    /// Decodes the value into an owned `Bson`.
+    pub fn to_bson(&self) -> DecoderResult<Bson> {
+        decode_bson(&mut &self.bytes[..], self.element_type.as_u8(), false)
+    }
+}
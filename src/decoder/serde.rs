@@ -2,6 +2,7 @@ use std::{fmt, vec};
 
 use serde::de::{
     self,
+    value::SeqDeserializer,
     Deserialize,
     DeserializeSeed,
     Deserializer,
@@ -15,10 +16,10 @@ use serde::de::{
 };
 
 use super::error::{DecoderError, DecoderResult};
-#[cfg(feature = "decimal128")]
-use crate::decimal128::Decimal128;
 use crate::{
     bson::{Binary, Bson, DbPointer, JavaScriptCodeWithScope, Regex, TimeStamp, UtcDateTime},
+    decimal128::Decimal128,
+    encoder::{BSON_NEWTYPE, DB_POINTER_NEWTYPE, UNDEFINED_UNIT},
     oid::ObjectId,
     ordered::{OrderedDocument, OrderedDocumentIntoIterator, OrderedDocumentVisitor},
     spec::BinarySubtype,
@@ -65,7 +66,7 @@ impl<'de> Deserialize<'de> for Bson {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(BsonVisitor)
+        deserializer.deserialize_newtype_struct(BSON_NEWTYPE, BsonVisitor)
     }
 }
 
@@ -229,6 +230,65 @@ impl<'de> Visitor<'de> for BsonVisitor {
             bytes: v.to_vec(),
         }))
     }
+
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Bson, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Bson, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (variant, access) = data.variant::<String>()?;
+        match variant.as_str() {
+            UNDEFINED_UNIT => {
+                access.unit_variant()?;
+                Ok(Bson::Undefined)
+            }
+            DB_POINTER_NEWTYPE => access.tuple_variant(2, DbPointerVisitor),
+            _ => Err(A::Error::unknown_variant(
+                &variant,
+                &[UNDEFINED_UNIT, DB_POINTER_NEWTYPE],
+            )),
+        }
+    }
+}
+
+// Reads the namespace and id bytes that `RawDecoder` hands over for a DBPointer.
+struct DbPointerVisitor;
+
+impl<'de> Visitor<'de> for DbPointerVisitor {
+    type Value = Bson;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a DBPointer namespace and id")
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Bson, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let namespace: String = seq
+            .next_element()?
+            .ok_or_else(|| Error::invalid_length(0, &self))?;
+        let id = match seq.next_element()? {
+            Some(Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes,
+            })) if bytes.len() == 12 => {
+                let mut id = [0; 12];
+                id.copy_from_slice(&bytes);
+                ObjectId::with_bytes(id)
+            }
+            _ => return Err(Error::invalid_length(1, &self)),
+        };
+
+        Ok(Bson::DbPointer(DbPointer::new(namespace, id)))
+    }
 }
 
 /// Serde Decoder
@@ -316,15 +376,15 @@ impl<'de> Deserializer<'de> for Decoder {
             Bson::I64(v) => visitor.visit_i64(v),
             Bson::Binary(Binary {
                 subtype: BinarySubtype::Generic,
-                ref bytes,
-            }) => visitor.visit_bytes(&bytes),
+                bytes,
+            }) => visitor.visit_byte_buf(bytes),
             binary @ Bson::Binary(..) => visitor.visit_map(MapDecoder {
-                iter: binary.to_extended_document().into_iter(),
+                iter: binary.to_extended_document()?.into_iter(),
                 value: None,
                 len: 2,
             }),
             _ => {
-                let doc = value.to_extended_document();
+                let doc = value.to_extended_document()?;
                 let len = doc.len();
                 visitor.visit_map(MapDecoder {
                     iter: doc.into_iter(),
@@ -408,6 +468,28 @@ impl<'de> Deserializer<'de> for Decoder {
         visitor.visit_newtype_struct(self)
     }
 
+    fn deserialize_tuple<V>(mut self, _len: usize, visitor: V) -> DecoderResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value.take() {
+            // Generic binary can fill fixed-size byte arrays.
+            Some(Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes,
+            })) => {
+                let mut seq = SeqDeserializer::<_, DecoderError>::new(bytes.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            value => {
+                self.value = value;
+                self.deserialize_any(visitor)
+            }
+        }
+    }
+
     forward_to_deserialize! {
         deserialize_bool();
         deserialize_u8();
@@ -430,7 +512,6 @@ impl<'de> Deserializer<'de> for Decoder {
         deserialize_unit_struct(name: &'static str);
         deserialize_tuple_struct(name: &'static str, len: usize);
         deserialize_struct(name: &'static str, fields: &'static [&'static str]);
-        deserialize_tuple(len: usize);
         deserialize_identifier();
         deserialize_ignored_any();
         deserialize_byte_buf();
@@ -724,7 +805,6 @@ impl<'de> Deserialize<'de> for Binary {
     }
 }
 
-#[cfg(feature = "decimal128")]
 impl<'de> Deserialize<'de> for Decimal128 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -742,6 +822,10 @@ impl<'de> Deserialize<'de> for UtcDateTime {
     where
         D: Deserializer<'de>,
     {
+        if !deserializer.is_human_readable() {
+            return i64::deserialize(deserializer).map(UtcDateTime::from_millis);
+        }
+
         match Bson::deserialize(deserializer)? {
             Bson::UtcDatetime(dt) => Ok(UtcDateTime(dt)),
             _ => Err(D::Error::custom("expecting UtcDateTime")),
@@ -0,0 +1,138 @@
+use std::io::{self, Cursor, Read};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use super::{decode_document_with_max_size, DecoderError, DecoderResult, MAX_BSON_SIZE};
+use crate::bson::Document;
+
+/// Lazily decodes a sequence of BSON documents written back to back, such as the contents of a
+/// `mongodump` file.
+///
+/// Each document is read in full according to its length prefix before being decoded, so the
+/// reader stays aligned on document boundaries. Iteration stops at the end of the stream, or
+/// after the first error.
+///
+/// Documents whose declared length exceeds the maximum size are rejected before anything is
+/// buffered. The limit defaults to 16MB, the largest document MongoDB accepts, and can be
+/// changed with `DocumentReader::with_max_size`.
+///
+/// ```rust
+/// use bson::{doc, encode_document, DocumentReader};
+///
+/// let mut buf = Vec::new();
+/// encode_document(&mut buf, &doc! { "x": 1 }).unwrap();
+/// encode_document(&mut buf, &doc! { "x": 2 }).unwrap();
+///
+/// let docs: Vec<_> = DocumentReader::new(&buf[..])
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(docs, vec![doc! { "x": 1 }, doc! { "x": 2 }]);
+/// ```
+pub struct DocumentReader<R: Read> {
+    reader: R,
+    max_size: usize,
+    done: bool,
+}
+
+impl<R: Read> DocumentReader<R> {
+    /// Starts reading documents from the current position of `reader`.
+    pub fn new(reader: R) -> DocumentReader<R> {
+        DocumentReader::with_max_size(reader, MAX_BSON_SIZE as usize)
+    }
+
+    /// Starts reading documents from the current position of `reader`, rejecting any document
+    /// whose declared length is greater than `max_size` bytes.
+    pub fn with_max_size(reader: R, max_size: usize) -> DocumentReader<R> {
+        DocumentReader {
+            reader,
+            max_size,
+            done: false,
+        }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    // Reads the length prefix of the next document, or `None` if the stream ended cleanly on a
+    // document boundary.
+    fn read_len(&mut self) -> DecoderResult<Option<i32>> {
+        let mut buf = [0; 4];
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(DecoderError::EndOfStream),
+                Ok(n) => filled += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(Some(LittleEndian::read_i32(&buf)))
+    }
+
+    fn read_document(&mut self) -> DecoderResult<Option<Document>> {
+        let len = match self.read_len()? {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+
+        if len < 5 {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!("invalid document length {}", len),
+            ));
+        }
+        if len as usize > self.max_size {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!(
+                    "document length {} exceeds the maximum of {} bytes",
+                    len, self.max_size
+                ),
+            ));
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&len.to_le_bytes());
+        (&mut self.reader)
+            .take(len as u64 - 4)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() != len as usize {
+            return Err(DecoderError::EndOfStream);
+        }
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let doc = decode_document_with_max_size(&mut cursor, self.max_size)?;
+        if cursor.position() != len as u64 {
+            return Err(DecoderError::InvalidLength(
+                len as usize,
+                format!(
+                    "document length {} does not match the {} bytes read",
+                    len,
+                    cursor.position()
+                ),
+            ));
+        }
+
+        Ok(Some(doc))
+    }
+}
+
+impl<R: Read> Iterator for DocumentReader<R> {
+    type Item = DecoderResult<Document>;
+
+    fn next(&mut self) -> Option<DecoderResult<Document>> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_document().transpose();
+        if let None | Some(Err(_)) = result {
+            self.done = true;
+        }
+        result
+    }
+}
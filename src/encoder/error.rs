@@ -12,6 +12,7 @@ pub enum EncoderError {
     Unknown(String),
     UnsupportedUnsignedType,
     UnsignedTypesValueExceedsRange(u64),
+    DocumentTooLarge(usize),
 }
 
 impl From<io::Error> for EncoderError {
@@ -38,6 +39,12 @@ impl fmt::Display for EncoderError {
                  size.",
                 value
             ),
+            EncoderError::DocumentTooLarge(size) => write!(
+                fmt,
+                "document size of {} bytes exceeds the maximum of {} bytes",
+                size,
+                super::MAX_BSON_SIZE
+            ),
         }
     }
 }
@@ -57,6 +64,7 @@ impl error::Error for EncoderError {
                 "BSON does not support unsigned types.
                  An attempt to encode the value: {} in a signed type failed due to the values size."
             }
+            EncoderError::DocumentTooLarge(_) => "document exceeds the maximum BSON size",
         }
     }
 
@@ -23,22 +23,28 @@
 
 mod error;
 mod serde;
+mod stream;
 
+pub(crate) use self::serde::{BSON_NEWTYPE, DB_POINTER_NEWTYPE, UNDEFINED_UNIT};
 pub use self::{
     error::{EncoderError, EncoderResult},
     serde::Encoder,
+    stream::DocumentEncoder,
 };
 
 use std::{io::Write, iter::IntoIterator, mem};
 
 use byteorder::{LittleEndian, WriteBytesExt};
-use chrono::Timelike;
 
-use crate::bson::{Binary, Bson, DbPointer, JavaScriptCodeWithScope, Regex};
-#[cfg(feature = "decimal128")]
-use crate::decimal128::Decimal128;
+use crate::{
+    bson::{Binary, Bson, DbPointer, Document, JavaScriptCodeWithScope, Regex, UtcDateTime},
+    decimal128::Decimal128,
+};
 use ::serde::Serialize;
 
+/// The maximum size of a BSON document accepted by the MongoDB server.
+pub(crate) const MAX_BSON_SIZE: usize = 16 * 1024 * 1024;
+
 fn write_string<W: Write + ?Sized>(writer: &mut W, s: &str) -> EncoderResult<()> {
     writer.write_i32::<LittleEndian>(s.len() as i32 + 1)?;
     writer.write_all(s.as_bytes())?;
@@ -67,11 +73,9 @@ fn write_f64<W: Write + ?Sized>(writer: &mut W, val: f64) -> EncoderResult<()> {
     writer.write_f64::<LittleEndian>(val).map_err(From::from)
 }
 
-#[cfg(feature = "decimal128")]
 #[inline]
 fn write_f128<W: Write + ?Sized>(writer: &mut W, val: Decimal128) -> EncoderResult<()> {
-    let raw = val.to_raw_bytes_le();
-    writer.write_all(&raw).map_err(From::from)
+    writer.write_all(&val.to_bytes()).map_err(From::from)
 }
 
 fn encode_array<W: Write + ?Sized>(writer: &mut W, arr: &[Bson]) -> EncoderResult<()> {
@@ -93,6 +97,9 @@ fn encode_array<W: Write + ?Sized>(writer: &mut W, arr: &[Bson]) -> EncoderResul
 ///
 /// Can encode any type which is iterable as `(key: &str, value: &Bson)` pairs,
 /// which generally means most maps.
+///
+/// No limit is placed on the size of the encoded document; use `encode_document_checked` to
+/// reject documents larger than the MongoDB server accepts.
 pub fn encode_document<
     'a,
     S: AsRef<str> + 'a,
@@ -116,6 +123,94 @@ pub fn encode_document<
     Ok(())
 }
 
+/// Attempt to encode a `Document` into a byte stream, failing if the encoded document would be
+/// larger than the 16MB maximum accepted by the MongoDB server.
+///
+/// Nothing is written to the stream when the document is too large.
+pub fn encode_document_checked<
+    'a,
+    S: AsRef<str> + 'a,
+    W: Write + ?Sized,
+    D: IntoIterator<Item = (&'a S, &'a Bson)> + Clone,
+>(
+    writer: &mut W,
+    doc: D,
+) -> EncoderResult<()> {
+    let len = document_len(doc.clone());
+    if len > MAX_BSON_SIZE {
+        return Err(EncoderError::DocumentTooLarge(len));
+    }
+
+    encode_document(writer, doc)
+}
+
+/// Encode a `Document` into a newly allocated buffer.
+///
+/// The encoded size is computed up front, so the buffer is allocated exactly once.
+pub fn to_vec(doc: &Document) -> EncoderResult<Vec<u8>> {
+    let mut buf = Vec::with_capacity(document_len(doc));
+    encode_document(&mut buf, doc)?;
+    Ok(buf)
+}
+
+#[inline]
+fn string_len(s: &str) -> usize {
+    mem::size_of::<i32>() + s.len() + 1
+}
+
+fn array_len(arr: &[Bson]) -> usize {
+    let elements: usize = arr
+        .iter()
+        .enumerate()
+        .map(|(key, val)| element_len(&key.to_string(), val))
+        .sum();
+
+    mem::size_of::<i32>() + elements + mem::size_of::<u8>()
+}
+
+/// Computes the number of bytes `encode_document` would write for `doc`.
+pub(crate) fn document_len<'a, S: AsRef<str> + 'a, D: IntoIterator<Item = (&'a S, &'a Bson)>>(
+    doc: D,
+) -> usize {
+    let elements: usize = doc
+        .into_iter()
+        .map(|(key, val)| element_len(key.as_ref(), val))
+        .sum();
+
+    mem::size_of::<i32>() + elements + mem::size_of::<u8>()
+}
+
+/// Computes the number of bytes `encode_bson` would write for `val` under `key`.
+fn element_len(key: &str, val: &Bson) -> usize {
+    1 + key.len() + 1 + value_len(val)
+}
+
+/// Computes the number of bytes `encode_bson` writes for `val` itself, excluding the element
+/// type and key.
+pub(crate) fn value_len(val: &Bson) -> usize {
+    match *val {
+        Bson::FloatingPoint(_) | Bson::I64(_) | Bson::TimeStamp(_) | Bson::UtcDatetime(_) => 8,
+        Bson::String(ref v) | Bson::JavaScriptCode(ref v) | Bson::Symbol(ref v) => string_len(v),
+        Bson::Array(ref v) => array_len(v),
+        Bson::Document(ref v) => document_len(v),
+        Bson::Boolean(_) => 1,
+        Bson::Regex(Regex {
+            ref pattern,
+            ref options,
+        }) => pattern.len() + 1 + options.len() + 1,
+        Bson::ObjectId(_) => 12,
+        Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+            ref code,
+            ref scope,
+        }) => mem::size_of::<i32>() + string_len(code) + document_len(scope),
+        Bson::I32(_) => 4,
+        Bson::Binary(Binary { ref bytes, .. }) => mem::size_of::<i32>() + 1 + bytes.len(),
+        Bson::Null | Bson::Undefined | Bson::MinKey | Bson::MaxKey => 0,
+        Bson::Decimal128(_) => 16,
+        Bson::DbPointer(DbPointer { ref namespace, .. }) => string_len(namespace) + 12,
+    }
+}
+
 fn encode_bson<W: Write + ?Sized>(writer: &mut W, key: &str, val: &Bson) -> EncoderResult<()> {
     writer.write_u8(val.element_type() as u8)?;
     write_cstring(writer, key)?;
@@ -156,13 +251,9 @@ fn encode_bson<W: Write + ?Sized>(writer: &mut W, key: &str, val: &Bson) -> Enco
             writer.write_u8(From::from(subtype))?;
             writer.write_all(bytes).map_err(From::from)
         }
-        Bson::UtcDatetime(ref v) => write_i64(
-            writer,
-            (v.timestamp() * 1000) + (v.nanosecond() / 1_000_000) as i64,
-        ),
+        Bson::UtcDatetime(v) => write_i64(writer, UtcDateTime(v).to_millis()),
         Bson::Null => Ok(()),
         Bson::Symbol(ref v) => write_string(writer, &v),
-        #[cfg(feature = "decimal128")]
         Bson::Decimal128(ref v) => write_f128(writer, v.clone()),
         Bson::Undefined => Ok(()),
         Bson::MinKey => Ok(()),
@@ -1,6 +1,7 @@
-use std::convert::TryFrom;
+use std::{cell::Cell, convert::TryFrom};
 
 use serde::ser::{
+    Error,
     Serialize,
     SerializeMap,
     SerializeSeq,
@@ -12,8 +13,6 @@ use serde::ser::{
     Serializer,
 };
 
-#[cfg(feature = "decimal128")]
-use crate::decimal128::Decimal128;
 use crate::{
     bson::{
         Array,
@@ -26,6 +25,7 @@ use crate::{
         TimeStamp,
         UtcDateTime,
     },
+    decimal128::Decimal128,
     oid::ObjectId,
     spec::BinarySubtype,
 };
@@ -73,18 +73,212 @@ impl Serialize for Bson {
             Bson::Null => serializer.serialize_unit(),
             Bson::I32(v) => serializer.serialize_i32(v),
             Bson::I64(v) => serializer.serialize_i64(v),
+            // Human-readable formats such as JSON would write raw bytes as an array of numbers,
+            // which can't be told apart from an array when read back.
             Bson::Binary(Binary {
                 subtype: BinarySubtype::Generic,
                 ref bytes,
-            }) => serializer.serialize_bytes(bytes),
-            _ => {
-                let doc = self.to_extended_document();
-                doc.serialize(serializer)
-            }
+            }) if !serializer.is_human_readable() => serializer.serialize_bytes(bytes),
+            _ => serializer.serialize_newtype_struct(BSON_NEWTYPE, &SpecificBson(self)),
         }
     }
 }
 
+// Name of the newtype struct that `Bson` wraps its BSON-specific variants in when serializing,
+// and that it deserializes itself as. Other formats see through it, while `Encoder` and
+// `RawDecoder` recognize it and handle those variants natively.
+pub(crate) const BSON_NEWTYPE: &str = "$__bson_Bson";
+
+thread_local! {
+    // Set by `Encoder` just before serializing the value wrapped in `BSON_NEWTYPE`, so that the
+    // value can tell it is being serialized by `Encoder` rather than by some other format.
+    static NATIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+// A BSON-specific variant, serialized natively for `Encoder` and as its extended JSON document
+// for every other format.
+struct SpecificBson<'a>(&'a Bson);
+
+impl Serialize for SpecificBson<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Clear the flag straight away, so that nothing serialized further down sees it.
+        if NATIVE.with(|native| native.replace(false)) {
+            return serialize_native(self.0, serializer);
+        }
+
+        let doc = self.0.to_extended_document().map_err(S::Error::custom)?;
+        doc.serialize(serializer)
+    }
+}
+
+// Names of the newtype and unit structs that `serialize_native` wraps the BSON-specific types in.
+// `Encoder` recognizes them and rebuilds the original variant.
+const BINARY_NEWTYPE: &str = "$__bson_Binary";
+const OBJECT_ID_NEWTYPE: &str = "$__bson_ObjectId";
+const UTC_DATETIME_NEWTYPE: &str = "$__bson_UtcDatetime";
+const TIMESTAMP_NEWTYPE: &str = "$__bson_TimeStamp";
+const REGEX_NEWTYPE: &str = "$__bson_Regex";
+const CODE_NEWTYPE: &str = "$__bson_JavaScriptCode";
+const CODE_WITH_SCOPE_NEWTYPE: &str = "$__bson_JavaScriptCodeWithScope";
+const SYMBOL_NEWTYPE: &str = "$__bson_Symbol";
+pub(crate) const DB_POINTER_NEWTYPE: &str = "$__bson_DbPointer";
+const DECIMAL128_NEWTYPE: &str = "$__bson_Decimal128";
+pub(crate) const UNDEFINED_UNIT: &str = "$__bson_Undefined";
+const MIN_KEY_UNIT: &str = "$__bson_MinKey";
+const MAX_KEY_UNIT: &str = "$__bson_MaxKey";
+
+// Serializes a byte slice with `serialize_bytes` rather than as a sequence of integers.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+// Serializes the variants that have no direct serde equivalent in the form they take in BSON
+// itself: generic binary as bytes, and the rest wrapped in the structs named above. Only `Encoder`
+// is handed these, since no other format would know to turn them back into the same variants.
+fn serialize_native<S>(bson: &Bson, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match *bson {
+        Bson::Binary(Binary {
+            subtype: BinarySubtype::Generic,
+            ref bytes,
+        }) => serializer.serialize_bytes(bytes),
+        Bson::Binary(Binary { subtype, ref bytes }) => serializer.serialize_newtype_struct(
+            BINARY_NEWTYPE,
+            &(i32::from(u8::from(subtype)), Bytes(bytes)),
+        ),
+        Bson::ObjectId(ref id) => {
+            serializer.serialize_newtype_struct(OBJECT_ID_NEWTYPE, &Bytes(&id.bytes()))
+        }
+        Bson::UtcDatetime(v) => {
+            serializer.serialize_newtype_struct(UTC_DATETIME_NEWTYPE, &UtcDateTime(v).to_millis())
+        }
+        // Packed like the BSON encoding, with the time in the high 32 bits.
+        Bson::TimeStamp(TimeStamp { time, increment }) => serializer.serialize_newtype_struct(
+            TIMESTAMP_NEWTYPE,
+            &(((u64::from(time) << 32) | u64::from(increment)) as i64),
+        ),
+        Bson::Regex(Regex {
+            ref pattern,
+            ref options,
+        }) => serializer.serialize_newtype_struct(REGEX_NEWTYPE, &(pattern, options)),
+        Bson::JavaScriptCode(ref code) => serializer.serialize_newtype_struct(CODE_NEWTYPE, code),
+        Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+            ref code,
+            ref scope,
+        }) => serializer.serialize_newtype_struct(CODE_WITH_SCOPE_NEWTYPE, &(code, scope)),
+        Bson::Symbol(ref symbol) => serializer.serialize_newtype_struct(SYMBOL_NEWTYPE, symbol),
+        Bson::DbPointer(DbPointer {
+            ref namespace,
+            ref id,
+        }) => serializer
+            .serialize_newtype_struct(DB_POINTER_NEWTYPE, &(namespace, Bytes(&id.bytes()))),
+        Bson::Decimal128(ref v) => {
+            serializer.serialize_newtype_struct(DECIMAL128_NEWTYPE, &Bytes(&v.to_bytes()))
+        }
+        Bson::Undefined => serializer.serialize_unit_struct(UNDEFINED_UNIT),
+        Bson::MinKey => serializer.serialize_unit_struct(MIN_KEY_UNIT),
+        Bson::MaxKey => serializer.serialize_unit_struct(MAX_KEY_UNIT),
+        _ => unreachable!(
+            "{:?} is serialized directly by Bson::serialize",
+            bson.element_type()
+        ),
+    }
+}
+
+// Rebuilds a variant that `serialize_native` wrapped in the newtype struct `name`, from the `Bson`
+// that `Encoder` produced for the wrapped value. Other newtype structs are transparent.
+fn from_native_newtype(name: &str, value: Bson) -> EncoderResult<Bson> {
+    if !name.starts_with("$__bson_") {
+        return Ok(value);
+    }
+
+    fn object_id(bytes: &[u8]) -> Option<ObjectId> {
+        let mut id = [0; 12];
+        if bytes.len() != id.len() {
+            return None;
+        }
+        id.copy_from_slice(bytes);
+        Some(ObjectId::with_bytes(id))
+    }
+
+    let bson = match (name, value) {
+        (BINARY_NEWTYPE, Bson::Array(ref v)) => match v.as_slice() {
+            [Bson::I32(subtype), Bson::Binary(Binary { bytes, .. })] => {
+                u8::try_from(*subtype).ok().map(|subtype| {
+                    Bson::Binary(Binary {
+                        subtype: subtype.into(),
+                        bytes: bytes.clone(),
+                    })
+                })
+            }
+            _ => None,
+        },
+        (OBJECT_ID_NEWTYPE, Bson::Binary(Binary { ref bytes, .. })) => {
+            object_id(bytes).map(Bson::ObjectId)
+        }
+        (UTC_DATETIME_NEWTYPE, Bson::I64(millis)) => {
+            Some(Bson::UtcDatetime(UtcDateTime::from_millis(millis).0))
+        }
+        (TIMESTAMP_NEWTYPE, Bson::I64(v)) => Some(Bson::TimeStamp(TimeStamp {
+            time: ((v as u64) >> 32) as u32,
+            increment: v as u32,
+        })),
+        (REGEX_NEWTYPE, Bson::Array(ref v)) => match v.as_slice() {
+            [Bson::String(pattern), Bson::String(options)] => Some(Bson::Regex(Regex {
+                pattern: pattern.clone(),
+                options: options.clone(),
+            })),
+            _ => None,
+        },
+        (CODE_NEWTYPE, Bson::String(code)) => Some(Bson::JavaScriptCode(code)),
+        (CODE_WITH_SCOPE_NEWTYPE, Bson::Array(ref v)) => match v.as_slice() {
+            [Bson::String(code), Bson::Document(scope)] => {
+                Some(Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+                    code: code.clone(),
+                    scope: scope.clone(),
+                }))
+            }
+            _ => None,
+        },
+        (SYMBOL_NEWTYPE, Bson::String(symbol)) => Some(Bson::Symbol(symbol)),
+        (DB_POINTER_NEWTYPE, Bson::Array(ref v)) => match v.as_slice() {
+            [Bson::String(namespace), Bson::Binary(Binary { bytes, .. })] => {
+                object_id(bytes).map(|id| {
+                    Bson::DbPointer(DbPointer {
+                        namespace: namespace.clone(),
+                        id,
+                    })
+                })
+            }
+            _ => None,
+        },
+        (DECIMAL128_NEWTYPE, Bson::Binary(Binary { ref bytes, .. })) => {
+            let mut buf = [0; 16];
+            if bytes.len() == buf.len() {
+                buf.copy_from_slice(bytes);
+                Some(Bson::Decimal128(Decimal128::from_bytes(buf)))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+
+    bson.ok_or_else(|| EncoderError::Unknown(format!("invalid value for {}", name)))
+}
+
 /// Serde Encoder
 pub struct Encoder;
 
@@ -225,8 +419,13 @@ impl Serializer for Encoder {
     }
 
     #[inline]
-    fn serialize_unit_struct(self, _name: &'static str) -> EncoderResult<Bson> {
-        self.serialize_unit()
+    fn serialize_unit_struct(self, name: &'static str) -> EncoderResult<Bson> {
+        match name {
+            UNDEFINED_UNIT => Ok(Bson::Undefined),
+            MIN_KEY_UNIT => Ok(Bson::MinKey),
+            MAX_KEY_UNIT => Ok(Bson::MaxKey),
+            _ => self.serialize_unit(),
+        }
     }
 
     #[inline]
@@ -242,13 +441,20 @@ impl Serializer for Encoder {
     #[inline]
     fn serialize_newtype_struct<T: ?Sized>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> EncoderResult<Bson>
     where
         T: Serialize,
     {
-        value.serialize(self)
+        if name == BSON_NEWTYPE {
+            NATIVE.with(|native| native.set(true));
+            let result = value.serialize(self);
+            NATIVE.with(|native| native.set(false));
+            return result;
+        }
+
+        from_native_newtype(name, value.serialize(self)?)
     }
 
     #[inline]
@@ -544,7 +750,6 @@ impl Serialize for Binary {
     }
 }
 
-#[cfg(feature = "decimal128")]
 impl Serialize for Decimal128 {
     #[inline]
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -562,6 +767,10 @@ impl Serialize for UtcDateTime {
     where
         S: Serializer,
     {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_i64(self.to_millis());
+        }
+
         // Cloning a `DateTime` is extremely cheap
         let value = Bson::UtcDatetime(self.0);
         value.serialize(serializer)
@@ -0,0 +1,116 @@
+use std::io::{Seek, SeekFrom, Write};
+
+use byteorder::WriteBytesExt;
+
+use super::{
+    encode_bson,
+    write_cstring,
+    write_f64,
+    write_i32,
+    write_i64,
+    write_string,
+    EncoderResult,
+};
+use crate::{bson::Bson, spec::ElementType};
+
+/// Incrementally writes a BSON document field by field, without building a `Document` in memory.
+///
+/// The document length is written as a placeholder up front and patched in by `finish`, so the
+/// underlying writer must be seekable.
+///
+/// ```rust
+/// use bson::{decode_document, doc, DocumentEncoder};
+/// use std::io::Cursor;
+///
+/// let mut encoder = DocumentEncoder::new(Cursor::new(Vec::new())).unwrap();
+/// encoder.write_str("name", "bson").unwrap();
+/// encoder
+///     .write_document("nested", |nested| nested.write_i32("x", 1))
+///     .unwrap();
+/// let mut buf = encoder.finish().unwrap();
+///
+/// buf.set_position(0);
+/// let doc = decode_document(&mut buf).unwrap();
+/// assert_eq!(doc, doc! { "name": "bson", "nested": { "x": 1 } });
+/// ```
+pub struct DocumentEncoder<W: Write + Seek> {
+    writer: W,
+    start: u64,
+}
+
+impl<W: Write + Seek> DocumentEncoder<W> {
+    /// Starts a new document at the current position of `writer`.
+    pub fn new(mut writer: W) -> EncoderResult<DocumentEncoder<W>> {
+        let start = writer.stream_position()?;
+        write_i32(&mut writer, 0)?;
+        Ok(DocumentEncoder { writer, start })
+    }
+
+    fn write_key(&mut self, element_type: ElementType, key: &str) -> EncoderResult<()> {
+        self.writer.write_u8(element_type as u8)?;
+        write_cstring(&mut self.writer, key)
+    }
+
+    /// Appends an arbitrary BSON value.
+    pub fn write_bson(&mut self, key: &str, val: &Bson) -> EncoderResult<()> {
+        encode_bson(&mut self.writer, key, val)
+    }
+
+    /// Appends a 32-bit integer.
+    pub fn write_i32(&mut self, key: &str, val: i32) -> EncoderResult<()> {
+        self.write_key(ElementType::Integer32Bit, key)?;
+        write_i32(&mut self.writer, val)
+    }
+
+    /// Appends a 64-bit integer.
+    pub fn write_i64(&mut self, key: &str, val: i64) -> EncoderResult<()> {
+        self.write_key(ElementType::Integer64Bit, key)?;
+        write_i64(&mut self.writer, val)
+    }
+
+    /// Appends a floating point value.
+    pub fn write_f64(&mut self, key: &str, val: f64) -> EncoderResult<()> {
+        self.write_key(ElementType::FloatingPoint, key)?;
+        write_f64(&mut self.writer, val)
+    }
+
+    /// Appends a boolean.
+    pub fn write_bool(&mut self, key: &str, val: bool) -> EncoderResult<()> {
+        self.write_key(ElementType::Boolean, key)?;
+        self.writer
+            .write_u8(if val { 0x01 } else { 0x00 })
+            .map_err(From::from)
+    }
+
+    /// Appends a UTF-8 string.
+    pub fn write_str(&mut self, key: &str, val: &str) -> EncoderResult<()> {
+        self.write_key(ElementType::Utf8String, key)?;
+        write_string(&mut self.writer, val)
+    }
+
+    /// Appends an embedded document whose fields are written by `f`.
+    pub fn write_document<F>(&mut self, key: &str, f: F) -> EncoderResult<()>
+    where
+        F: FnOnce(&mut DocumentEncoder<&mut W>) -> EncoderResult<()>,
+    {
+        self.write_key(ElementType::EmbeddedDocument, key)?;
+
+        let mut nested = DocumentEncoder::new(&mut self.writer)?;
+        f(&mut nested)?;
+        nested.finish()?;
+        Ok(())
+    }
+
+    /// Terminates the document, patches its length and returns the underlying writer, positioned
+    /// just past the end of the document.
+    pub fn finish(mut self) -> EncoderResult<W> {
+        self.writer.write_u8(0)?;
+
+        let end = self.writer.stream_position()?;
+        self.writer.seek(SeekFrom::Start(self.start))?;
+        write_i32(&mut self.writer, (end - self.start) as i32)?;
+        self.writer.seek(SeekFrom::Start(end))?;
+
+        Ok(self.writer)
+    }
+}
@@ -39,8 +39,6 @@
 //! let doc = decode_document(&mut Cursor::new(&buf[..])).unwrap();
 //! ```
 
-#[cfg(feature = "decimal128")]
-pub use self::decimal128::Decimal128;
 pub use self::{
     bson::{
         Array,
@@ -49,20 +47,40 @@ pub use self::{
         DbPointer,
         Document,
         JavaScriptCodeWithScope,
+        PathSegment,
         Regex,
+        RegexFlags,
         TimeStamp,
         UtcDateTime,
     },
+    decimal128::Decimal128,
     decoder::{
         decode_document,
         decode_document_utf8_lossy,
+        decode_document_with_max_size,
         from_bson,
+        from_reader_to_json,
+        from_slice,
         Decoder,
         DecoderError,
         DecoderResult,
+        DocumentReader,
+        RawBson,
+        RawDecoder,
+        RawDocument,
+        RawDocumentIter,
+    },
+    encoder::{
+        encode_document,
+        encode_document_checked,
+        to_bson,
+        to_vec,
+        DocumentEncoder,
+        Encoder,
+        EncoderError,
+        EncoderResult,
     },
-    encoder::{encode_document, to_bson, Encoder, EncoderError, EncoderResult},
-    ordered::{ValueAccessError, ValueAccessResult},
+    ordered::{DocumentBuilder, ValueAccessError, ValueAccessResult},
 };
 
 #[macro_use]
@@ -71,6 +89,9 @@ mod bson;
 pub mod compat;
 #[cfg(feature = "decimal128")]
 pub mod decimal128;
+#[cfg(not(feature = "decimal128"))]
+#[path = "decimal128_bytes.rs"]
+pub mod decimal128;
 mod decoder;
 mod encoder;
 pub mod oid;
@@ -4,11 +4,14 @@ use std::{
     error,
     fmt,
     result,
+    str::FromStr,
     sync::atomic::{AtomicUsize, Ordering},
 };
 
 use byteorder::{BigEndian, ByteOrder};
 
+use chrono::{offset::TimeZone, DateTime, Utc};
+
 use hex::{self, FromHexError};
 
 use rand::{thread_rng, Rng};
@@ -107,8 +110,32 @@ impl ObjectId {
         ObjectId { id: bytes }
     }
 
+    /// Constructs an ObjectId with the given creation time and all other bytes zeroed.
+    ///
+    /// ObjectIds order by their bytes, which begin with the big-endian creation time, so the
+    /// result is a lower bound for ids generated at or after `time`. This makes it useful as a
+    /// boundary in `_id` range queries.
+    ///
+    /// The creation time is stored as unsigned 32-bit seconds since the Unix epoch, so times
+    /// before 1970 or after 2106-02-07T06:28:15Z are clamped to the earliest or latest time that
+    /// fits.
+    pub fn from_timestamp(time: DateTime<Utc>) -> ObjectId {
+        let secs = time.timestamp().clamp(0, i64::from(u32::MAX));
+        let mut buf: [u8; 12] = [0; 12];
+        BigEndian::write_u32(
+            &mut buf[TIMESTAMP_OFFSET..(TIMESTAMP_SIZE + TIMESTAMP_OFFSET)],
+            secs as u32,
+        );
+        ObjectId::with_bytes(buf)
+    }
+
     /// Creates an ObjectID using a 12-byte (24-char) hexadecimal string.
     pub fn with_string(s: &str) -> Result<ObjectId> {
+        ObjectId::parse_bytes(s).map(ObjectId::with_bytes)
+    }
+
+    /// Parses a 24-character hexadecimal string into the raw 12 bytes of an ObjectId.
+    pub fn parse_bytes(s: &str) -> Result<[u8; 12]> {
         let bytes: Vec<u8> = hex::decode(s.as_bytes())?;
         if bytes.len() != 12 {
             Err(Error::ArgumentError(
@@ -117,7 +144,7 @@ impl ObjectId {
         } else {
             let mut byte_array: [u8; 12] = [0; 12];
             byte_array[..].copy_from_slice(&bytes[..]);
-            Ok(ObjectId::with_bytes(byte_array))
+            Ok(byte_array)
         }
     }
 
@@ -131,6 +158,33 @@ impl ObjectId {
         hex::encode(self.id)
     }
 
+    /// Returns the creation time embedded in the ObjectId, in seconds since the Unix epoch.
+    pub fn time(&self) -> u32 {
+        BigEndian::read_u32(&self.id[TIMESTAMP_OFFSET..(TIMESTAMP_SIZE + TIMESTAMP_OFFSET)])
+    }
+
+    /// Returns the creation time embedded in the ObjectId.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        // Every u32 number of seconds is in range for chrono, so this cannot fail.
+        Utc.timestamp_opt(i64::from(self.time()), 0).unwrap()
+    }
+
+    /// Returns the 5-byte process identifier embedded in the ObjectId.
+    ///
+    /// Note that `ObjectId::new` currently fills these bytes with a fresh random value for each
+    /// ObjectId, so they are not shared between ObjectIds generated by the same process.
+    pub fn process_id(&self) -> [u8; 5] {
+        let mut buf = [0; PROCESS_ID_SIZE];
+        buf.copy_from_slice(&self.id[PROCESS_ID_OFFSET..(PROCESS_ID_SIZE + PROCESS_ID_OFFSET)]);
+        buf
+    }
+
+    /// Returns the 3-byte counter embedded in the ObjectId, which is incremented for each
+    /// ObjectId generated by this process.
+    pub fn counter(&self) -> u32 {
+        BigEndian::read_u24(&self.id[COUNTER_OFFSET..(COUNTER_SIZE + COUNTER_OFFSET)])
+    }
+
     // Generates a new timestamp representing the current seconds since epoch.
     // Represented in Big Endian.
     fn gen_timestamp() -> [u8; 4] {
@@ -177,6 +231,14 @@ impl ObjectId {
     }
 }
 
+impl FromStr for ObjectId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<ObjectId> {
+        ObjectId::with_string(s)
+    }
+}
+
 impl fmt::Display for ObjectId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(&self.to_hex())
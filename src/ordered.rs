@@ -1,10 +1,12 @@
 //! A BSON document represented as an associative HashMap with insertion ordering.
 
 use std::{
+    collections::HashMap,
     error,
     fmt::{self, Debug, Display, Formatter},
     iter::{Extend, FromIterator, Map},
     marker::PhantomData,
+    mem,
 };
 
 use chrono::{DateTime, Utc};
@@ -13,12 +15,13 @@ use linked_hash_map::{self, LinkedHashMap};
 
 use serde::de::{self, MapAccess, Visitor};
 
-#[cfg(feature = "decimal128")]
-use crate::decimal128::Decimal128;
 use crate::{
     bson::{Array, Binary, Bson, Document, TimeStamp},
+    decimal128::Decimal128,
+    decoder::{DecoderError, DecoderResult},
+    encoder,
     oid::ObjectId,
-    spec::BinarySubtype,
+    spec::{BinarySubtype, ElementType},
 };
 
 /// Error to indicate that either a value was empty or it contained an unexpected
@@ -210,6 +213,27 @@ impl OrderedDocument {
         self.inner.get_mut(key)
     }
 
+    /// Looks up a nested value by a dotted path such as `"a.b.0"`. See `Bson::get_path`.
+    pub fn get_path(&self, path: &str) -> Option<&Bson> {
+        let mut segments = path.splitn(2, '.');
+        let value = self.get(segments.next()?)?;
+        match segments.next() {
+            Some(rest) => value.get_path(rest),
+            None => Some(value),
+        }
+    }
+
+    /// Looks up a nested value by a dotted path, returning a mutable reference. See
+    /// `Bson::get_path_mut`.
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Bson> {
+        let mut segments = path.splitn(2, '.');
+        let value = self.get_mut(segments.next()?)?;
+        match segments.next() {
+            Some(rest) => value.get_path_mut(rest),
+            None => Some(value),
+        }
+    }
+
     /// Get a floating point value for this key if it exists and has
     /// the correct type.
     pub fn get_f64(&self, key: &str) -> ValueAccessResult<f64> {
@@ -231,7 +255,6 @@ impl OrderedDocument {
     }
 
     /// Get a reference to a Decimal128 value for key, if it exists.
-    #[cfg(feature = "decimal128")]
     pub fn get_decimal128(&self, key: &str) -> ValueAccessResult<&Decimal128> {
         match self.get(key) {
             Some(&Bson::Decimal128(ref v)) => Ok(v),
@@ -241,7 +264,6 @@ impl OrderedDocument {
     }
 
     /// Get a mutable reference to a Decimal128 value for key, if it exists.
-    #[cfg(feature = "decimal128")]
     pub fn get_decimal128_mut(&mut self, key: &str) -> ValueAccessResult<&mut Decimal128> {
         match self.get_mut(key) {
             Some(&mut Bson::Decimal128(ref mut v)) => Ok(v),
@@ -386,6 +408,26 @@ impl OrderedDocument {
         }
     }
 
+    /// Get a reference to a binary value of any subtype for this key if it exists and has the
+    /// correct type.
+    pub fn get_binary(&self, key: &str) -> ValueAccessResult<&Binary> {
+        match self.get(key) {
+            Some(Bson::Binary(v)) => Ok(v),
+            Some(_) => Err(ValueAccessError::UnexpectedType),
+            None => Err(ValueAccessError::NotPresent),
+        }
+    }
+
+    /// Get a mutable reference to a binary value of any subtype for this key if it exists and has
+    /// the correct type.
+    pub fn get_binary_mut(&mut self, key: &str) -> ValueAccessResult<&mut Binary> {
+        match self.get_mut(key) {
+            Some(Bson::Binary(v)) => Ok(v),
+            Some(_) => Err(ValueAccessError::UnexpectedType),
+            None => Err(ValueAccessError::NotPresent),
+        }
+    }
+
     /// Get a reference to a generic binary value for this key if it exists and has the correct
     /// type.
     pub fn get_binary_generic(&self, key: &str) -> ValueAccessResult<&Vec<u8>> {
@@ -489,6 +531,12 @@ impl OrderedDocument {
         self.inner.is_empty()
     }
 
+    /// Returns true if the encoded document would be larger than the 16MB maximum accepted by the
+    /// MongoDB server.
+    pub fn exceeds_size_limit(&self) -> bool {
+        encoder::document_len(self) > encoder::MAX_BSON_SIZE
+    }
+
     /// Sets the value of the entry with the OccupiedEntry's key,
     /// and returns the entry's old value. Accepts any type that
     /// can be converted into Bson.
@@ -501,29 +549,354 @@ impl OrderedDocument {
         self.inner.remove(key)
     }
 
-    pub fn entry(&mut self, k: String) -> Entry {
+    /// Keeps only the entries for which `f` returns `true`, preserving their order.
+    pub fn retain<F: FnMut(&str, &Bson) -> bool>(&mut self, mut f: F) {
+        self.remove_matching(|key, value| !f(key, value));
+    }
+
+    /// Removes the entries for which `f` returns `true`, returning them in their original order.
+    /// The remaining entries keep their order.
+    pub fn remove_matching<F: FnMut(&str, &Bson) -> bool>(&mut self, mut f: F) -> Document {
+        let entries = mem::replace(&mut self.inner, LinkedHashMap::new());
+        let mut removed = Document::new();
+
+        for (key, value) in entries {
+            if f(&key, &value) {
+                removed.inner.insert(key, value);
+            } else {
+                self.inner.insert(key, value);
+            }
+        }
+
+        removed
+    }
+
+    /// Converts the document into a vector of its entries, in insertion order.
+    pub fn into_vec(self) -> Vec<(String, Bson)> {
+        let mut vec = Vec::with_capacity(self.len());
+        vec.extend(self);
+        vec
+    }
+
+    /// Builds a document from a vector of entries, preserving their order. Later entries
+    /// overwrite earlier entries with the same key.
+    pub fn from_vec(vec: Vec<(String, Bson)>) -> OrderedDocument {
+        let mut inner = LinkedHashMap::with_capacity(vec.len());
+        for (k, v) in vec {
+            inner.insert(k, v);
+        }
+        inner.into()
+    }
+
+    /// Converts an array-like document, whose keys are exactly the indices `"0"` to `"n - 1"`,
+    /// into an array with its values in index order. Any other document is handed back unchanged
+    /// as the error.
+    pub fn try_into_array(self) -> Result<Array, OrderedDocument> {
+        fn index(key: &str, len: usize) -> Option<usize> {
+            // Reject forms like "01" or "+1" that parse but are not canonical indices.
+            key.parse::<usize>()
+                .ok()
+                .filter(|&idx| idx < len && idx.to_string() == key)
+        }
+
+        let len = self.len();
+        if !self.keys().all(|key| index(key, len).is_some()) {
+            return Err(self);
+        }
+
+        // Keys are unique, so every slot gets filled exactly once.
+        let mut slots = vec![None; len];
+        for (key, value) in self {
+            if let Some(idx) = index(&key, len) {
+                slots[idx] = Some(value);
+            }
+        }
+        Ok(slots.into_iter().flatten().collect())
+    }
+
+    /// Counts how many values of each element type appear in the document.
+    ///
+    /// If `recursive` is true, the values inside embedded documents and arrays are counted as
+    /// well, in addition to the containing document or array itself.
+    pub fn type_histogram(&self, recursive: bool) -> HashMap<ElementType, usize> {
+        fn count(value: &Bson, recursive: bool, histogram: &mut HashMap<ElementType, usize>) {
+            *histogram.entry(value.element_type()).or_insert(0) += 1;
+
+            if !recursive {
+                return;
+            }
+
+            match *value {
+                Bson::Document(ref doc) => {
+                    for v in doc.values() {
+                        count(v, recursive, histogram);
+                    }
+                }
+                Bson::Array(ref arr) => {
+                    for v in arr {
+                        count(v, recursive, histogram);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut histogram = HashMap::new();
+        for value in self.values() {
+            count(value, recursive, &mut histogram);
+        }
+        histogram
+    }
+
+    /// Replaces every `.` and any leading `$` in the document's keys with `replacement`, making
+    /// them safe to use as MongoDB field names. Embedded documents, including those inside arrays,
+    /// are sanitized as well.
+    ///
+    /// Entries keep their order. If two keys sanitize to the same name, the later value
+    /// overwrites the earlier one.
+    pub fn sanitize_keys(&mut self, replacement: char) {
+        fn sanitize_value(value: &mut Bson, replacement: char) {
+            match *value {
+                Bson::Document(ref mut doc) => doc.sanitize_keys(replacement),
+                Bson::Array(ref mut arr) => {
+                    for v in arr {
+                        sanitize_value(v, replacement);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let entries = mem::replace(&mut self.inner, LinkedHashMap::new());
+        self.inner.reserve(entries.len());
+
+        for (mut key, mut value) in entries {
+            if key.contains('.') {
+                key = key.replace('.', replacement.encode_utf8(&mut [0; 4]));
+            }
+            if key.starts_with('$') {
+                key.replace_range(..1, replacement.encode_utf8(&mut [0; 4]));
+            }
+
+            sanitize_value(&mut value, replacement);
+            self.inner.insert(key, value);
+        }
+    }
+
+    /// Flattens nested documents and arrays into a single-level document whose keys are dotted
+    /// paths, such as `"a.b.0"`, in the form accepted by `get_path`. Empty documents and arrays
+    /// have no leaves to expand into, so they are kept as values.
+    pub fn flatten(&self) -> Document {
+        fn flatten_value(prefix: String, value: &Bson, flat: &mut Document) {
+            match *value {
+                Bson::Document(ref doc) if !doc.is_empty() => {
+                    for (k, v) in doc {
+                        flatten_value(format!("{}.{}", prefix, k), v, flat);
+                    }
+                }
+                Bson::Array(ref arr) if !arr.is_empty() => {
+                    for (i, v) in arr.iter().enumerate() {
+                        flatten_value(format!("{}.{}", prefix, i), v, flat);
+                    }
+                }
+                _ => {
+                    flat.insert(prefix, value.clone());
+                }
+            }
+        }
+
+        let mut flat = Document::new();
+        for (k, v) in self {
+            flatten_value(k.clone(), v, &mut flat);
+        }
+        flat
+    }
+
+    /// Recursively merges `other` into this document. Where both documents hold an embedded
+    /// document under the same key, the two are merged; any other value in `other`, including
+    /// arrays, replaces the existing one in place. Keys only present in this document are left
+    /// untouched, and new keys are appended.
+    pub fn merge(&mut self, other: Document) {
+        for (key, value) in other {
+            match (self.get_mut(&key), value) {
+                (Some(Bson::Document(existing)), Bson::Document(patch)) => existing.merge(patch),
+                (Some(existing), value) => *existing = value,
+                (None, value) => {
+                    self.insert(key, value);
+                }
+            }
+        }
+    }
+
+    /// Checks that each key in `schema` is present with the given element type, returning an
+    /// error naming the first field that is missing or has a different type. Keys that are not
+    /// part of the schema are ignored.
+    pub fn validate(&self, schema: &[(&str, ElementType)]) -> DecoderResult<()> {
+        for &(key, expected) in schema {
+            match self.get(key) {
+                None => {
+                    return Err(DecoderError::InvalidValue(format!(
+                        "missing required field \"{}\"",
+                        key
+                    )))
+                }
+                Some(value) if value.element_type() != expected => {
+                    return Err(DecoderError::InvalidType(format!(
+                        "field \"{}\" has type {:?}, expected {:?}",
+                        key,
+                        value.element_type(),
+                        expected
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the entry for `key`, for in-place updates that insert the value if it is missing.
+    /// Newly inserted keys are added to the end of the document.
+    ///
+    /// ```rust
+    /// use bson::{doc, Bson};
+    ///
+    /// let mut counts = doc! {};
+    /// for word in &["a", "b", "a"] {
+    ///     counts
+    ///         .entry(*word)
+    ///         .and_modify(|count| {
+    ///             if let Bson::I32(ref mut count) = *count {
+    ///                 *count += 1;
+    ///             }
+    ///         })
+    ///         .or_insert(Bson::I32(1));
+    /// }
+    ///
+    /// assert_eq!(counts, doc! { "a": 2, "b": 1 });
+    /// ```
+    pub fn entry<KT: Into<String>>(&mut self, key: KT) -> Entry {
         Entry {
-            inner: self.inner.entry(k),
+            inner: self.inner.entry(key.into()),
         }
     }
 }
 
+/// A view into a single field of a `Document`, which may or may not be present. Returned by
+/// `Document::entry`.
 pub struct Entry<'a> {
     inner: linked_hash_map::Entry<'a, String, Bson>,
 }
 
 impl<'a> Entry<'a> {
+    /// The key of the entry.
     pub fn key(&self) -> &str {
         self.inner.key()
     }
 
+    /// Inserts `default` if the entry is missing, and returns a mutable reference to the value.
     pub fn or_insert(self, default: Bson) -> &'a mut Bson {
         self.inner.or_insert(default)
     }
 
+    /// Inserts the result of `default` if the entry is missing, and returns a mutable reference
+    /// to the value.
     pub fn or_insert_with<F: FnOnce() -> Bson>(self, default: F) -> &'a mut Bson {
         self.inner.or_insert_with(default)
     }
+
+    /// Calls `f` on the value if the entry is present, before any insert.
+    pub fn and_modify<F: FnOnce(&mut Bson)>(self, f: F) -> Entry<'a> {
+        Entry {
+            inner: self.inner.and_modify(f),
+        }
+    }
+}
+
+/// Builds a `Document` one field at a time, for when the `doc!` macro is not a good fit, such as
+/// when fields are added conditionally.
+///
+/// ```rust
+/// use bson::{doc, DocumentBuilder};
+///
+/// let verbose = true;
+/// let mut builder = DocumentBuilder::new().str("name", "bson").i32("stars", 10);
+/// if verbose {
+///     builder = builder.bool("verbose", true);
+/// }
+///
+/// assert_eq!(
+///     builder.build(),
+///     doc! { "name": "bson", "stars": 10, "verbose": true }
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DocumentBuilder {
+    doc: OrderedDocument,
+}
+
+impl DocumentBuilder {
+    /// Starts building an empty document.
+    pub fn new() -> DocumentBuilder {
+        DocumentBuilder::default()
+    }
+
+    /// Inserts a value of any type convertible to `Bson`. As with `Document::insert`, a repeated
+    /// key replaces the earlier value.
+    pub fn field<KT: Into<String>, BT: Into<Bson>>(mut self, key: KT, val: BT) -> DocumentBuilder {
+        self.doc.insert(key, val);
+        self
+    }
+
+    /// Inserts a string value.
+    pub fn str<KT: Into<String>, VT: Into<String>>(self, key: KT, val: VT) -> DocumentBuilder {
+        self.field(key, Bson::String(val.into()))
+    }
+
+    /// Inserts an i32 value.
+    pub fn i32<KT: Into<String>>(self, key: KT, val: i32) -> DocumentBuilder {
+        self.field(key, Bson::I32(val))
+    }
+
+    /// Inserts an i64 value.
+    pub fn i64<KT: Into<String>>(self, key: KT, val: i64) -> DocumentBuilder {
+        self.field(key, Bson::I64(val))
+    }
+
+    /// Inserts a floating point value.
+    pub fn f64<KT: Into<String>>(self, key: KT, val: f64) -> DocumentBuilder {
+        self.field(key, Bson::FloatingPoint(val))
+    }
+
+    /// Inserts a bool value.
+    pub fn bool<KT: Into<String>>(self, key: KT, val: bool) -> DocumentBuilder {
+        self.field(key, Bson::Boolean(val))
+    }
+
+    /// Inserts a null value.
+    pub fn null<KT: Into<String>>(self, key: KT) -> DocumentBuilder {
+        self.field(key, Bson::Null)
+    }
+
+    /// Inserts an object id value.
+    pub fn object_id<KT: Into<String>>(self, key: KT, val: ObjectId) -> DocumentBuilder {
+        self.field(key, Bson::ObjectId(val))
+    }
+
+    /// Inserts an embedded document.
+    pub fn doc<KT: Into<String>>(self, key: KT, val: Document) -> DocumentBuilder {
+        self.field(key, Bson::Document(val))
+    }
+
+    /// Inserts an array.
+    pub fn array<KT: Into<String>>(self, key: KT, val: Array) -> DocumentBuilder {
+        self.field(key, Bson::Array(val))
+    }
+
+    /// Returns the document built so far.
+    pub fn build(self) -> Document {
+        self.doc
+    }
 }
 
 impl From<LinkedHashMap<String, Bson>> for OrderedDocument {
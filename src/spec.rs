@@ -23,6 +23,8 @@
 
 use std::convert::From;
 
+use crate::decoder::{DecoderError, DecoderResult};
+
 const ELEMENT_TYPE_FLOATING_POINT: u8 = 0x01;
 const ELEMENT_TYPE_UTF8_STRING: u8 = 0x02;
 const ELEMENT_TYPE_EMBEDDED_DOCUMENT: u8 = 0x03;
@@ -52,12 +54,13 @@ const BINARY_SUBTYPE_BINARY_OLD: u8 = 0x02;
 const BINARY_SUBTYPE_UUID_OLD: u8 = 0x03;
 const BINARY_SUBTYPE_UUID: u8 = 0x04;
 const BINARY_SUBTYPE_MD5: u8 = 0x05;
+const BINARY_SUBTYPE_USER_DEFINED: u8 = 0x80;
 
 /// All available BSON element types.
 ///
 /// Not all element types are representable by the `Bson` type.
 #[repr(u8)]
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum ElementType {
     /// 64-bit binary floating point
     FloatingPoint = ELEMENT_TYPE_FLOATING_POINT,
@@ -101,14 +104,14 @@ pub enum ElementType {
     /// 64-bit integer
     Integer64Bit = ELEMENT_TYPE_64BIT_INTEGER,
     /// [128-bit decimal floating point](https://github.com/mongodb/specifications/blob/master/source/bson-decimal128/decimal128.rst)
-    #[cfg(feature = "decimal128")]
     Decimal128Bit = ELEMENT_TYPE_128BIT_DECIMAL,
     MaxKey = ELEMENT_TYPE_MAXKEY,
     MinKey = ELEMENT_TYPE_MINKEY,
 }
 
 impl ElementType {
-    /// Attempt to convert from a `u8`.
+    /// Attempt to convert from the tag byte that precedes each element in an encoded document.
+    /// Returns `None` if `tag` is not a known element type.
     #[inline]
     pub fn from(tag: u8) -> Option<ElementType> {
         use self::ElementType::*;
@@ -131,13 +134,18 @@ impl ElementType {
             ELEMENT_TYPE_32BIT_INTEGER => Integer32Bit,
             ELEMENT_TYPE_TIMESTAMP => TimeStamp,
             ELEMENT_TYPE_64BIT_INTEGER => Integer64Bit,
-            #[cfg(feature = "decimal128")]
             ELEMENT_TYPE_128BIT_DECIMAL => Decimal128Bit,
             ELEMENT_TYPE_MAXKEY => MaxKey,
             ELEMENT_TYPE_MINKEY => MinKey,
             _ => return None,
         })
     }
+
+    /// The tag byte identifying this element type in an encoded document.
+    #[inline]
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
 }
 
 /// The available binary subtypes, plus a user-defined slot.
@@ -152,6 +160,30 @@ pub enum BinarySubtype {
     UserDefined(u8),
 }
 
+impl BinarySubtype {
+    /// Constructs a user-defined subtype. Returns an error if `n` is below 0x80, since those values
+    /// are reserved by the specification.
+    pub fn user_defined(n: u8) -> DecoderResult<BinarySubtype> {
+        if n < BINARY_SUBTYPE_USER_DEFINED {
+            return Err(DecoderError::InvalidValue(format!(
+                "binary subtype {:#04x} is reserved; user-defined subtypes start at {:#04x}",
+                n, BINARY_SUBTYPE_USER_DEFINED
+            )));
+        }
+
+        Ok(BinarySubtype::UserDefined(n))
+    }
+
+    /// Whether this subtype falls in the user-defined range 0x80-0xFF. Unknown subtypes below
+    /// that range are reserved rather than user-defined.
+    pub fn is_user_defined(&self) -> bool {
+        match *self {
+            BinarySubtype::UserDefined(n) => n >= BINARY_SUBTYPE_USER_DEFINED,
+            _ => false,
+        }
+    }
+}
+
 impl From<BinarySubtype> for u8 {
     #[inline]
     fn from(t: BinarySubtype) -> u8 {
@@ -1,13 +1,21 @@
 use bson::{
+    bson,
     doc,
     oid::ObjectId,
     spec::BinarySubtype,
     Binary,
     Bson,
+    DbPointer,
+    DecoderError,
     Document,
     JavaScriptCodeWithScope,
+    PathSegment,
     Regex,
+    RegexFlags,
+    TimeStamp,
+    UtcDateTime,
 };
+use chrono::{offset::TimeZone, Utc};
 use serde_json::{json, Value};
 
 #[test]
@@ -140,3 +148,875 @@ fn from_impls() {
     let db_pointer = db_pointer.as_db_pointer().unwrap();
     assert_eq!(Bson::from(db_pointer), Bson::DbPointer(db_pointer.clone()));
 }
+
+#[test]
+fn regex_flags() {
+    let regex = Regex {
+        pattern: String::from("^foo"),
+        options: String::from("imx"),
+    };
+    let flags = regex.flags();
+    assert_eq!(
+        flags,
+        RegexFlags::CASE_INSENSITIVE | RegexFlags::MULTILINE | RegexFlags::EXTENDED
+    );
+    assert_eq!(Regex::from_flags("^foo", flags), regex);
+
+    let unsorted = Regex::from_flags(
+        "^foo",
+        RegexFlags::UNICODE | RegexFlags::DOTALL | RegexFlags::LOCALE,
+    );
+    assert_eq!(unsorted.options, "lsu");
+    assert_eq!(Regex::from_flags("^foo", RegexFlags::empty()).options, "");
+}
+
+#[test]
+fn get_path() {
+    let doc = doc! {
+        "a": { "b": [{ "c": 1 }, "x"] },
+        "min": Bson::MinKey,
+        "max": { "k": Bson::MaxKey },
+    };
+
+    assert_eq!(doc.get_path("a.b.0.c"), Some(&Bson::I32(1)));
+    assert_eq!(doc.get_path("a.b.1"), Some(&Bson::String("x".to_owned())));
+    assert_eq!(doc.get_path("a.b.2"), None);
+    assert_eq!(doc.get_path("a.b.c"), None);
+    assert_eq!(doc.get_path("a.missing"), None);
+
+    // Sentinel and scalar values end the walk.
+    assert_eq!(doc.get_path("min"), Some(&Bson::MinKey));
+    assert_eq!(doc.get_path("min.x"), None);
+    assert_eq!(doc.get_path("max.k.0"), None);
+    assert_eq!(doc.get_path("a.b.1.0"), None);
+    assert_eq!(Bson::MinKey.get_path("0"), None);
+}
+
+#[test]
+fn get_path_mut() {
+    let mut doc = doc! { "a": { "b": [{ "c": 1 }, "x"] } };
+
+    *doc.get_path_mut("a.b.0.c").unwrap() = Bson::I32(2);
+    assert_eq!(doc, doc! { "a": { "b": [{ "c": 2 }, "x"] } });
+
+    let mut bson = Bson::Document(doc);
+    if let Some(Bson::String(s)) = bson.get_path_mut("a.b.1") {
+        s.push('y');
+    }
+    assert_eq!(bson.get_path("a.b.1"), Some(&Bson::String("xy".to_owned())));
+
+    assert_eq!(bson.get_path_mut("a.b.2"), None);
+    assert_eq!(bson.get_path_mut("a.b.1.0"), None);
+}
+
+#[test]
+fn approx_eq() {
+    let sum = Bson::FloatingPoint(0.1 + 0.2);
+    let expected = Bson::FloatingPoint(0.3);
+    assert_ne!(sum, expected);
+    assert!(sum.approx_eq(&expected, 1e-9));
+    assert!(!Bson::FloatingPoint(0.31).approx_eq(&expected, 1e-9));
+
+    assert!(Bson::I32(3).approx_eq(&Bson::FloatingPoint(3.0000001), 1e-6));
+    assert!(Bson::I64(3).approx_eq(&Bson::I32(3), 0.0));
+    assert!(Bson::FloatingPoint(f64::INFINITY).approx_eq(&Bson::FloatingPoint(f64::INFINITY), 1e-9));
+    assert!(!Bson::String("3".to_owned()).approx_eq(&Bson::I32(3), 1.0));
+
+    let a = bson!({ "x": [0.1 + 0.2, "s"], "y": { "z": 1.0 / 3.0 } });
+    let b = bson!({ "x": [0.3, "s"], "y": { "z": 0.333_333_333_333 } });
+    assert!(a.approx_eq(&b, 1e-9));
+    assert!(!a.approx_eq(&bson!({ "x": [0.3, "t"], "y": { "z": 0.3 } }), 1e-9));
+    assert!(!a.approx_eq(&bson!({ "y": { "z": 1.0 / 3.0 }, "x": [0.3, "s"] }), 1e-9));
+}
+
+#[test]
+fn from_extended_json_str() {
+    assert_eq!(
+        Bson::from_extended_json_str(r#"{ "$numberInt": "5" }"#).unwrap(),
+        Bson::I32(5)
+    );
+
+    let date = Utc.timestamp_opt(1_577_836_800, 0).unwrap();
+    assert_eq!(
+        Bson::from_extended_json_str(r#"{ "$date": "2020-01-01T00:00:00Z" }"#).unwrap(),
+        Bson::UtcDatetime(date)
+    );
+    assert_eq!(
+        Bson::from_extended_json_str(r#"{ "$date": { "$numberLong": 1577836800000 } }"#).unwrap(),
+        Bson::UtcDatetime(date)
+    );
+
+    assert_eq!(
+        Bson::from_extended_json_str(
+            r#"{ "a": [1, "two"], "b": { "$oid": "5e5efe0b0000000000000000" } }"#
+        )
+        .unwrap(),
+        bson!({
+            "a": [1_i64, "two"],
+            "b": ObjectId::with_string("5e5efe0b0000000000000000").unwrap(),
+        })
+    );
+
+    assert!(Bson::from_extended_json_str(r#"{ "a": "#).is_err());
+}
+
+#[test]
+fn to_extended_document_unsupported_type() {
+    assert!(Bson::I32(5).to_extended_document().is_err());
+    assert!(Bson::String("s".to_owned()).to_extended_document().is_err());
+    assert_eq!(
+        Bson::MinKey.to_extended_document().unwrap(),
+        doc! { "$minKey": 1 }
+    );
+}
+
+#[test]
+fn from_extended_json_number_long() {
+    assert_eq!(
+        Bson::from(json!({ "$numberLong": "9000000000" })),
+        Bson::I64(9_000_000_000)
+    );
+    assert_eq!(Bson::from(json!({ "$numberLong": "-1" })), Bson::I64(-1));
+
+    // Not a valid 64-bit integer, so it stays a plain document.
+    assert_eq!(
+        Bson::from(json!({ "$numberLong": "1.5" })),
+        bson!({ "$numberLong": "1.5" })
+    );
+}
+
+#[test]
+fn from_extended_json_canonical_date() {
+    let expected = Bson::UtcDatetime(Utc.timestamp_millis_opt(1_577_836_800_000).unwrap());
+
+    assert_eq!(
+        Bson::from_extended_json_str(r#"{ "$date": { "$numberLong": "1577836800000" } }"#).unwrap(),
+        expected
+    );
+    assert_eq!(
+        Bson::from_extended_document(doc! { "$date": { "$numberLong": "1577836800000" } }),
+        expected
+    );
+    assert_eq!(
+        Bson::from_extended_document(doc! { "$date": { "$numberLong": 1_577_836_800_000_i64 } }),
+        expected
+    );
+}
+
+#[test]
+fn from_extended_json_code_with_extra_keys() {
+    // Parsing extended JSON reports extra keys alongside `$code`, naming the keys allowed.
+    for json in &[
+        r#"{ "$code": "x", "$scope": { "x": 1 }, "extra": 1 }"#,
+        r#"{ "$code": "x", "extra": 1 }"#,
+    ] {
+        match Bson::from_extended_json_str(json) {
+            Err(DecoderError::InvalidValue(msg)) => {
+                assert!(msg.contains("\"extra\""), "{}", msg);
+                assert!(msg.contains("\"$code\" and \"$scope\""), "{}", msg);
+            }
+            other => panic!("expected an error for {}, got {:?}", json, other),
+        }
+    }
+    assert!(Bson::try_from_extended_document(doc! { "$code": "x", "extra": 1 }).is_err());
+
+    // Converting a `Value` with `Bson::from` is lenient: the document is not a code value, so it
+    // is kept as is.
+    assert_eq!(
+        Bson::from(json!({ "$code": "x", "$scope": { "x": 1 }, "extra": 1 })),
+        bson!({ "$code": "x", "$scope": { "x": 1_i64 }, "extra": 1_i64 })
+    );
+    assert_eq!(
+        Bson::from(json!({ "$code": "x", "extra": 1 })),
+        bson!({ "$code": "x", "extra": 1_i64 })
+    );
+
+    assert_eq!(
+        Bson::from(json!({ "$code": "x" })),
+        Bson::JavaScriptCode("x".to_owned())
+    );
+}
+
+#[test]
+fn from_extended_json_str_reports_malformed_documents() {
+    // `$code` and `$scope` together are still a code with scope value.
+    assert_eq!(
+        Bson::from_extended_json_str(r#"{ "$code": "x", "$scope": { "x": 1 } }"#).unwrap(),
+        Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope::new("x", doc! { "x": 1_i64 }))
+    );
+
+    // Extra fields next to extended JSON keys are reported, at any depth.
+    assert!(Bson::from_extended_json_str(r#"{ "$code": "x", "$scope": {}, "extra": 1 }"#).is_err());
+    assert!(Bson::from_extended_json_str(r#"{ "a": { "$oid": "x", "b": 1 } }"#).is_err());
+    assert!(Bson::from_extended_json_str(r#"[{ "$date": true }]"#).is_err());
+
+    // Two keys that don't form a valid extended type are reported too.
+    assert!(Bson::from_extended_json_str(r#"{ "$code": 1, "$scope": {} }"#).is_err());
+
+    // Query operators are not extended JSON keys.
+    assert_eq!(
+        Bson::from_extended_json_str(r#"{ "$and": [], "$or": [], "$nor": [] }"#).unwrap(),
+        bson!({ "$and": [], "$or": [], "$nor": [] })
+    );
+
+    // Converting a `Value` directly stays lenient.
+    assert_eq!(
+        Bson::from(json!({ "$code": "x", "extra": 1 })),
+        bson!({ "$code": "x", "extra": 1_i64 })
+    );
+}
+
+#[test]
+fn from_canonical_extjson() {
+    let canonical = json!({
+        "int": { "$numberInt": "5" },
+        "long": { "$numberLong": "9000000000" },
+        "double": { "$numberDouble": "-1.5" },
+        "inf": { "$numberDouble": "-Infinity" },
+        "date": { "$date": { "$numberLong": "1577836800000" } },
+        "binary": { "$binary": { "base64": "AQI=", "subType": "80" } },
+        "ts": { "$timestamp": { "t": 4_294_967_295_u32, "i": 1 } },
+        "pointer": { "$dbPointer": { "$ref": "db.coll", "$id": { "$oid": "5e5efe0b0000000000000000" } } },
+        "code": { "$code": "x", "$scope": { "x": { "$numberInt": "1" } } },
+        "plain": ["s", true, null, { "$set": { "a": { "$minKey": 1 } } }],
+    });
+
+    let id = ObjectId::with_string("5e5efe0b0000000000000000").unwrap();
+    assert_eq!(
+        Bson::from_canonical_extjson(canonical).unwrap(),
+        bson!({
+            "int": 5,
+            "long": 9_000_000_000_i64,
+            "double": -1.5,
+            "inf": f64::NEG_INFINITY,
+            "date": UtcDateTime::from_millis(1_577_836_800_000),
+            "binary": Binary { subtype: BinarySubtype::UserDefined(0x80), bytes: vec![1, 2] },
+            "ts": TimeStamp::new(4_294_967_295, 1),
+            "pointer": Bson::DbPointer(DbPointer::new("db.coll", id)),
+            "code": JavaScriptCodeWithScope::new("x", doc! { "x": 1 }),
+            "plain": ["s", true, Bson::Null, { "$set": { "a": Bson::MinKey } }],
+        })
+    );
+}
+
+#[test]
+fn from_canonical_extjson_rejects_relaxed_forms() {
+    let relaxed = [
+        json!({ "x": 5 }),
+        json!([1.5]),
+        json!({ "$date": "2020-01-01T00:00:00Z" }),
+        json!({ "$date": 1_577_836_800_000_i64 }),
+        json!({ "$date": { "$numberLong": 1_577_836_800_000_i64 } }),
+        json!({ "$numberLong": 5 }),
+        json!({ "$numberDouble": "inf" }),
+        json!({ "$binary": "0102", "type": 0 }),
+        json!({ "$dbPointer": { "$ref": "db.coll", "$id": "5e5efe0b0000000000000000" } }),
+        json!({ "$minKey": { "$numberInt": "1" } }),
+    ];
+
+    for value in relaxed.iter() {
+        // Each is understood by the lenient conversion...
+        let lenient = Bson::from(value.clone());
+        let mut keys = lenient.as_document().into_iter().flat_map(Document::keys);
+        assert!(keys.all(|k| !k.starts_with('$')), "{}", value);
+        // ...but not by the strict one.
+        assert!(
+            Bson::from_canonical_extjson(value.clone()).is_err(),
+            "{}",
+            value
+        );
+    }
+
+    // Malformed canonical types are rejected too.
+    assert!(Bson::from_canonical_extjson(json!({ "$numberInt": "5", "extra": true })).is_err());
+    assert!(Bson::from_canonical_extjson(json!({ "$oid": "x" })).is_err());
+    assert!(Bson::from_canonical_extjson(json!({ "$timestamp": { "t": -1, "i": 0 } })).is_err());
+}
+
+#[test]
+fn extended_json_user_defined_binary_subtype() {
+    let binary = Bson::Binary(Binary::new(BinarySubtype::UserDefined(0x81), vec![1, 2]));
+
+    // The legacy form this crate writes keeps the subtype as a number.
+    let json = Value::from(binary.clone());
+    assert_eq!(json, json!({ "$binary": "0102", "type": 0x81 }));
+    assert_eq!(Bson::from(json), binary);
+
+    // The canonical form stores it as a hex string, which both conversions accept.
+    let json = json!({ "$binary": { "base64": "AQI=", "subType": "81" } });
+    assert_eq!(Bson::from(json.clone()), binary);
+    assert_eq!(Bson::from_canonical_extjson(json).unwrap(), binary);
+    assert_eq!(
+        Bson::from(json!({ "$binary": { "base64": "AQI=", "subType": "5" } })),
+        Bson::Binary(Binary::new(BinarySubtype::Md5, vec![1, 2]))
+    );
+
+    // Subtypes that don't fit in a byte are left as plain documents rather than truncated.
+    assert_eq!(
+        Bson::from(json!({ "$binary": "0102", "type": 0x181 })),
+        bson!({ "$binary": "0102", "type": 0x181_i64 })
+    );
+    assert!(
+        Bson::from(json!({ "$binary": { "base64": "AQI=", "subType": "181" } }))
+            .as_document()
+            .is_some()
+    );
+    assert!(Bson::from_canonical_extjson(
+        json!({ "$binary": { "base64": "AQI=", "subType": "+1" } })
+    )
+    .is_err());
+}
+
+#[test]
+fn from_extended_json_regular_expression() {
+    assert_eq!(
+        Bson::from(json!({ "$regularExpression": { "pattern": "^a.*b$", "options": "im" } })),
+        Bson::Regex(Regex {
+            pattern: "^a.*b$".to_owned(),
+            options: "im".to_owned(),
+        })
+    );
+
+    // Sub-documents missing a field or carrying extra ones are not regular expressions.
+    let missing = json!({ "$regularExpression": { "pattern": "a" } });
+    assert_eq!(
+        Bson::from(missing),
+        bson!({ "$regularExpression": { "pattern": "a" } })
+    );
+    let extra = json!({ "$regularExpression": { "pattern": "a", "options": "", "x": 1 } });
+    assert!(Bson::from(extra).as_document().is_some());
+}
+
+#[cfg(feature = "decimal128")]
+#[test]
+fn decimal128_to_extended_json() {
+    use bson::decimal128::Decimal128;
+
+    let dec = Decimal128::from_str("968E+1");
+    let doc = Bson::Document(doc! { "d": Bson::Decimal128(dec.clone()) });
+
+    let value: Value = doc.clone().into();
+    assert_eq!(value, json!({ "d": { "$numberDecimal": dec.to_string() } }));
+    assert_eq!(Bson::from(value), doc);
+}
+
+#[test]
+fn into_json() {
+    let id = ObjectId::with_string("5e5efe0b0000000000000000").unwrap();
+    let date = Utc.timestamp_opt(1_577_836_800, 123_000_000).unwrap();
+    let doc = bson!({
+        "_id": id.clone(),
+        "date": Bson::UtcDatetime(date),
+        "data": Binary { subtype: BinarySubtype::Generic, bytes: b"hello".to_vec() },
+        "nested": [{ "n": 1 }, 2.5, "s", Bson::Null],
+    });
+
+    let extended: Value = doc.clone().into();
+    assert_eq!(
+        extended["_id"],
+        json!({ "$oid": "5e5efe0b0000000000000000" })
+    );
+    assert_eq!(
+        extended["date"],
+        json!({ "$date": { "$numberLong": 1_577_836_800_123_i64 } })
+    );
+
+    assert_eq!(
+        doc.into_json(),
+        json!({
+            "_id": "5e5efe0b0000000000000000",
+            "date": "2020-01-01T00:00:00.123Z",
+            "data": "aGVsbG8=",
+            "nested": [{ "n": 1 }, 2.5, "s", null],
+        })
+    );
+}
+
+#[test]
+fn timestamp_ordering() {
+    let ts = TimeStamp::new(1, 2);
+    assert_eq!(
+        ts,
+        TimeStamp {
+            time: 1,
+            increment: 2,
+        }
+    );
+
+    assert!(TimeStamp::new(1, 2) < TimeStamp::new(1, 3));
+    assert!(TimeStamp::new(1, 3) < TimeStamp::new(2, 0));
+    assert!(TimeStamp::new(1, u32::MAX) < TimeStamp::new(2, 0));
+
+    let mut timestamps = vec![
+        TimeStamp::new(2, 0),
+        TimeStamp::new(1, 3),
+        TimeStamp::new(1, 2),
+    ];
+    timestamps.sort();
+    assert_eq!(
+        timestamps,
+        [
+            TimeStamp::new(1, 2),
+            TimeStamp::new(1, 3),
+            TimeStamp::new(2, 0),
+        ]
+    );
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn uuid_roundtrip() {
+    let uuid = uuid::Uuid::parse_str("936da01f-9abd-4d9d-80c7-02af85c822a8").unwrap();
+
+    let binary = Binary::from_uuid(uuid);
+    assert_eq!(binary.subtype, BinarySubtype::Uuid);
+    assert_eq!(&binary.bytes[..], uuid.as_bytes());
+    assert_eq!(binary.to_uuid().unwrap(), uuid);
+
+    let generic = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes: uuid.as_bytes().to_vec(),
+    };
+    assert!(generic.to_uuid().is_err());
+
+    let short = Binary {
+        subtype: BinarySubtype::Uuid,
+        bytes: vec![0; 15],
+    };
+    assert!(short.to_uuid().is_err());
+}
+
+#[test]
+fn binary_constructors() {
+    assert_eq!(
+        Binary::generic(vec![1, 2, 3]),
+        Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: vec![1, 2, 3],
+        }
+    );
+    assert_eq!(
+        Binary::new(BinarySubtype::UserDefined(0x80), vec![4]),
+        Binary {
+            subtype: BinarySubtype::UserDefined(0x80),
+            bytes: vec![4],
+        }
+    );
+}
+
+#[test]
+fn date_json_out_of_range_years() {
+    // 10000-01-01T00:00:00Z
+    let far_future = UtcDateTime::from_millis(253_402_300_800_000);
+    let canonical = json!({ "$date": { "$numberLong": 253_402_300_800_000_i64 } });
+    assert_eq!(Value::from(Bson::UtcDatetime(*far_future)), canonical);
+    assert_eq!(Bson::UtcDatetime(*far_future).into_json(), canonical);
+    assert_eq!(
+        Bson::from_extended_json_str(&canonical.to_string()).unwrap(),
+        Bson::UtcDatetime(*far_future)
+    );
+
+    // 1969-12-31T23:59:58.500Z
+    let before_epoch = UtcDateTime::from_millis(-1_500);
+    assert_eq!(
+        Value::from(Bson::UtcDatetime(*before_epoch)),
+        json!({ "$date": { "$numberLong": -1_500 } })
+    );
+    let relaxed = Bson::UtcDatetime(*before_epoch).into_json();
+    assert_eq!(relaxed, json!("1969-12-31T23:59:58.500Z"));
+    assert_eq!(
+        Bson::from(json!({ "$date": relaxed })),
+        Bson::UtcDatetime(*before_epoch)
+    );
+
+    // The extremes of the representable range don't panic.
+    for &millis in &[i64::MIN, i64::MAX] {
+        let date = Bson::UtcDatetime(*UtcDateTime::from_millis(millis));
+        assert_eq!(Value::from(date.clone()), date.clone().into_json());
+    }
+}
+
+#[test]
+fn utc_date_time_millis() {
+    let date = UtcDateTime::from_millis(1_577_836_800_123);
+    assert_eq!(
+        *date,
+        Utc.timestamp_opt(1_577_836_800, 123_000_000).unwrap()
+    );
+    assert_eq!(date.to_millis(), 1_577_836_800_123);
+
+    // Negative values count back from the epoch, so -1500ms is 1.5 seconds before it.
+    let date = UtcDateTime::from_millis(-1_500);
+    assert_eq!(*date, Utc.timestamp_opt(-2, 500_000_000).unwrap());
+    assert_eq!(date.to_millis(), -1_500);
+
+    assert_eq!(UtcDateTime::from_millis(-1).to_millis(), -1);
+    assert!(UtcDateTime::from_millis(i64::MIN) < UtcDateTime::from_millis(0));
+    assert!(UtcDateTime::from_millis(i64::MAX) > UtcDateTime::from_millis(0));
+
+    let before = Utc::now();
+    let now = UtcDateTime::now();
+    assert!(*now >= before && *now <= Utc::now());
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_compile() {
+    let regex = Regex::new("foo", "i");
+    assert_eq!(
+        regex,
+        Regex {
+            pattern: "foo".to_owned(),
+            options: "i".to_owned(),
+        }
+    );
+
+    let compiled = regex.compile().unwrap();
+    assert!(compiled.is_match("FoO"));
+    assert!(!compiled.is_match("bar"));
+
+    assert!(Regex::new("^b", "m").compile().unwrap().is_match("a\nb"));
+    assert!(Regex::new("foo", "l").compile().is_err());
+    assert!(Regex::new("(", "").compile().is_err());
+}
+
+#[test]
+fn regex_normalized() {
+    assert_eq!(
+        Regex::new("foo", "xmii").normalized().unwrap(),
+        Regex::new("foo", "imx")
+    );
+    assert_eq!(
+        Regex::new("foo", "").normalized().unwrap(),
+        Regex::new("foo", "")
+    );
+
+    assert!(Regex::new("foo", "iq").normalized().is_err());
+}
+
+#[test]
+fn code_with_scope_constructor() {
+    let mut code_with_scope = JavaScriptCodeWithScope::new("x + y", doc! { "x": 1 });
+    assert_eq!(
+        code_with_scope,
+        JavaScriptCodeWithScope {
+            code: "x + y".to_owned(),
+            scope: doc! { "x": 1 },
+        }
+    );
+
+    code_with_scope.scope_mut().insert("y", 2);
+    assert_eq!(code_with_scope.scope, doc! { "x": 1, "y": 2 });
+}
+
+#[test]
+fn db_pointer_constructor() {
+    let id = ObjectId::with_string("507f1f77bcf86cd799439011").unwrap();
+    let db_pointer = DbPointer::new("db.coll", id.clone());
+    assert_eq!(db_pointer.namespace(), "db.coll");
+    assert_eq!(db_pointer.id(), &id);
+
+    let bson = Bson::from(db_pointer.clone());
+    assert_eq!(bson, Bson::DbPointer(db_pointer));
+}
+
+#[test]
+fn double_json_roundtrip() {
+    for &v in &[
+        5.0,
+        -0.0,
+        1.0e300,
+        5e-324,
+        -2.2e-308,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+    ] {
+        let json = Value::from(Bson::FloatingPoint(v));
+        match Bson::from(json.clone()) {
+            Bson::FloatingPoint(f) => assert_eq!(f.to_bits(), v.to_bits()),
+            other => panic!("{} decoded as {:?}", v, other),
+        }
+
+        match Bson::from_extended_json_str(&json.to_string()).unwrap() {
+            Bson::FloatingPoint(f) => assert_eq!(f.to_bits(), v.to_bits()),
+            other => panic!("{} decoded as {:?}", json, other),
+        }
+    }
+
+    let nan = Value::from(Bson::FloatingPoint(f64::NAN));
+    assert_eq!(nan, json!({ "$numberDouble": "NaN" }));
+    assert!(Bson::from(nan).as_f64().unwrap().is_nan());
+}
+
+#[test]
+fn type_name() {
+    assert_eq!(Bson::I32(1).type_name(), "int32");
+    assert_eq!(Bson::I64(1).type_name(), "int64");
+    assert_eq!(Bson::FloatingPoint(1.0).type_name(), "double");
+    assert_eq!(bson!({ "a": 1 }).type_name(), "document");
+    assert_eq!(bson!([1]).type_name(), "array");
+    assert_eq!(Bson::ObjectId(ObjectId::new()).type_name(), "objectId");
+    assert_eq!(
+        Bson::TimeStamp(TimeStamp::new(1, 1)).type_name(),
+        "timestamp"
+    );
+    assert_eq!(Bson::Null.type_name(), "null");
+    assert_eq!(Bson::MinKey.type_name(), "minKey");
+}
+
+#[test]
+fn array_and_document_iter() {
+    let array = bson!([1, "two", 3.0]);
+    let collected: Vec<&Bson> = array.array_iter().unwrap().collect();
+    assert_eq!(
+        collected,
+        vec![
+            &Bson::I32(1),
+            &Bson::String("two".to_owned()),
+            &Bson::FloatingPoint(3.0)
+        ]
+    );
+    assert!(array.document_iter().is_none());
+
+    let doc = bson!({ "a": 1, "b": "two" });
+    let keys: Vec<&String> = doc.document_iter().unwrap().map(|(k, _)| k).collect();
+    assert_eq!(keys, vec!["a", "b"]);
+    assert_eq!(doc.document_iter().unwrap().count(), 2);
+    assert!(doc.array_iter().is_none());
+
+    assert!(Bson::Null.array_iter().is_none());
+}
+
+#[test]
+fn lossy_numeric_accessors() {
+    assert_eq!(Bson::I32(5).as_i64_lossy(), Some(5_i64));
+    assert_eq!(Bson::I64(1 << 40).as_i64_lossy(), Some(1 << 40));
+    assert_eq!(Bson::FloatingPoint(5.0).as_i64_lossy(), None);
+    assert_eq!(Bson::String("5".to_owned()).as_i64_lossy(), None);
+
+    assert_eq!(Bson::I32(5).as_f64_lossy(), Some(5.0));
+    assert_eq!(Bson::I64(-3).as_f64_lossy(), Some(-3.0));
+    assert_eq!(Bson::FloatingPoint(1.5).as_f64_lossy(), Some(1.5));
+    assert_eq!(Bson::Null.as_f64_lossy(), None);
+
+    // The strict accessors are unchanged.
+    assert_eq!(Bson::I32(5).as_i64(), None);
+    assert_eq!(Bson::I32(5).as_f64(), None);
+}
+
+#[test]
+fn array_with_capacity() {
+    let mut array = Bson::array_with_capacity(100);
+    let capacity = array.as_array().unwrap().capacity();
+    assert!(capacity >= 100);
+
+    for i in 0..100 {
+        array.push(i).unwrap();
+    }
+    assert_eq!(array.as_array().unwrap().capacity(), capacity);
+    assert_eq!(array.as_array().unwrap().len(), 100);
+    assert_eq!(array.get_path("99"), Some(&Bson::I32(99)));
+
+    let mut not_array = Bson::Null;
+    assert_eq!(not_array.push("x"), Err(Bson::String("x".to_owned())));
+    assert_eq!(not_array, Bson::Null);
+}
+
+#[test]
+fn partial_eq_primitives() {
+    let s = Bson::String("hello".to_owned());
+    assert!(s == "hello");
+    assert!(s == *"hello");
+    let owned = String::from("hello");
+    assert!(s == owned);
+    assert!(s != "world");
+    assert!(Bson::I32(1) != "1");
+
+    assert!(Bson::I32(5) == 5_i32);
+    assert!(Bson::I32(5) != 6_i32);
+    assert!(Bson::I64(5) == 5_i64);
+    assert!(Bson::I64(5) != 6_i64);
+    // No numeric coercion between variants.
+    assert!(Bson::I64(5) != 5_i32);
+    assert!(Bson::I32(5) != 5_i64);
+
+    assert!(Bson::FloatingPoint(1.5) == 1.5);
+    assert!(Bson::FloatingPoint(1.5) != 2.5);
+    assert!(Bson::I32(1) != 1.0);
+
+    assert!(Bson::Boolean(true) == true);
+    assert!(Bson::Boolean(true) != false);
+    assert!(Bson::Null != false);
+}
+
+#[test]
+fn json_by_reference() {
+    let bson = bson!({
+        "_id": ObjectId::with_string("5e5efe0b0000000000000000").unwrap(),
+        "date": Bson::UtcDatetime(Utc.timestamp_opt(1_577_836_800, 0).unwrap()),
+        "data": (Binary::generic(b"hello".to_vec())),
+        "regex": (Regex::new("^a", "i")),
+        "code": (JavaScriptCodeWithScope::new("x", doc! { "x": [1, Bson::Undefined] })),
+        "ts": (Bson::TimeStamp(TimeStamp::new(1, 2))),
+        "nan": (f64::NAN),
+        "nested": [{ "n": 1 }, 2.5, "s", Bson::Null, Bson::MinKey],
+    });
+
+    assert_eq!(bson.to_json(), bson.clone().into_json());
+    assert_eq!(bson.to_extended_json(), Value::from(bson.clone()));
+}
+
+#[test]
+fn from_value_no_parse() {
+    let value = json!({
+        "_id": { "$oid": "5e5efe0b0000000000000000" },
+        "n": [1, 2.5, { "$numberInt": "5" }],
+        "s": "text",
+        "null": null,
+    });
+
+    assert_eq!(
+        Bson::from_value_no_parse(value.clone()),
+        bson!({
+            "_id": { "$oid": "5e5efe0b0000000000000000" },
+            "n": [1_i64, 2.5, { "$numberInt": "5" }],
+            "s": "text",
+            "null": Bson::Null,
+        })
+    );
+
+    // `Bson::from` interprets the same value as extended JSON.
+    assert_eq!(
+        Bson::from(value).as_document().unwrap().get("_id"),
+        Some(&Bson::ObjectId(
+            ObjectId::with_string("5e5efe0b0000000000000000").unwrap()
+        ))
+    );
+}
+
+#[test]
+fn variant_conversions() {
+    let date = UtcDateTime::from_millis(1_577_836_800_123);
+    let timestamp = TimeStamp::new(1, 2);
+    let regex = Regex::new("^a", "i");
+
+    assert_eq!(Bson::from(5_i32), Bson::I32(5));
+    assert_eq!(Bson::from(5_i64), Bson::I64(5));
+    assert_eq!(Bson::from(1.5), Bson::FloatingPoint(1.5));
+    assert_eq!(Bson::from(date), Bson::UtcDatetime(*date));
+    assert_eq!(Bson::from(timestamp), Bson::TimeStamp(timestamp));
+    assert_eq!(Bson::from(regex.clone()), Bson::Regex(regex.clone()));
+
+    // The extended types survive a trip through extended JSON.
+    for value in &[
+        Bson::UtcDatetime(*date),
+        Bson::TimeStamp(timestamp),
+        Bson::Regex(regex),
+    ] {
+        assert_eq!(
+            &Bson::from_extended_document(value.to_extended_document().unwrap()),
+            value
+        );
+        assert_eq!(&Bson::from(Value::from(value.clone())), value);
+    }
+}
+
+#[test]
+fn try_into_document_and_array() {
+    assert_eq!(bson!({ "a": 1 }).try_into_document(), Ok(doc! { "a": 1 }));
+    assert_eq!(Bson::I32(1).try_into_document(), Err(Bson::I32(1)));
+
+    assert_eq!(
+        bson!([1, "two"]).try_into_array(),
+        Ok(vec![Bson::I32(1), Bson::from("two")])
+    );
+    assert_eq!(bson!({ "0": 1 }).try_into_array(), Err(bson!({ "0": 1 })));
+}
+
+#[test]
+fn to_document() {
+    let value = bson!({ "a": 1, "b": { "c": "d" } });
+    assert_eq!(
+        value.to_document().unwrap(),
+        doc! { "a": 1, "b": { "c": "d" } }
+    );
+    assert_eq!(
+        value
+            .as_document()
+            .unwrap()
+            .get("b")
+            .unwrap()
+            .to_document()
+            .unwrap(),
+        doc! { "c": "d" }
+    );
+
+    match Bson::I32(1).to_document() {
+        Err(DecoderError::InvalidType(msg)) => assert!(msg.contains("Integer32Bit"), "{}", msg),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(bson!([{ "a": 1 }]).to_document().is_err());
+}
+
+#[test]
+fn for_each() {
+    let value = bson!({
+        "name": "root",
+        "tags": ["a", 1, { "label": "b" }],
+        "meta": { "owner": { "name": "c" }, "count": 2 },
+    });
+
+    let mut strings = Vec::new();
+    value.for_each(|path, value| {
+        if let Bson::String(ref s) = *value {
+            strings.push((path.to_vec(), s.as_str()));
+        }
+    });
+
+    use PathSegment::{Index, Key};
+    assert_eq!(
+        strings,
+        vec![
+            (vec![Key("name")], "root"),
+            (vec![Key("tags"), Index(0)], "a"),
+            (vec![Key("tags"), Index(2), Key("label")], "b"),
+            (vec![Key("meta"), Key("owner"), Key("name")], "c"),
+        ]
+    );
+
+    let mut visited = 0;
+    value.for_each(|path, _| {
+        if visited == 0 {
+            assert!(path.is_empty());
+        }
+        visited += 1;
+    });
+    assert_eq!(visited, 11);
+}
+
+#[test]
+fn binary_display() {
+    let small = Binary::new(BinarySubtype::Md5, vec![1, 2, 3]);
+    assert_eq!(small.to_string(), "BinData(5, 0x010203)");
+    assert_eq!(Bson::Binary(small).to_string(), "BinData(5, 0x010203)");
+
+    let large = Binary::generic(vec![0xab; 4 * 1024 * 1024]);
+    let display = Bson::Binary(large.clone()).to_string();
+    assert_eq!(
+        display,
+        format!("BinData(0, 0x{}..., 4194304 bytes)", "ab".repeat(16))
+    );
+    assert!(display.len() < 100);
+    assert_eq!(large.to_hex().len(), 8 * 1024 * 1024);
+}
+
+#[test]
+fn as_bytes() {
+    let generic = Bson::Binary(Binary::generic(vec![1, 2, 3]));
+    assert_eq!(generic.as_bytes(), Some(&[1, 2, 3][..]));
+
+    let md5 = Bson::Binary(Binary::new(BinarySubtype::Md5, vec![4; 16]));
+    assert_eq!(md5.as_bytes(), Some(&[4; 16][..]));
+
+    assert_eq!(Bson::String("abc".to_owned()).as_bytes(), None);
+}
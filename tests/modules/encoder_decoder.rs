@@ -1,20 +1,35 @@
+use assert_matches::assert_matches;
 #[cfg(feature = "decimal128")]
 use bson::decimal128::Decimal128;
 use bson::{
     decode_document,
     decode_document_utf8_lossy,
+    decode_document_with_max_size,
     doc,
     encode_document,
+    encode_document_checked,
+    from_reader_to_json,
+    from_slice,
     oid::ObjectId,
-    spec::BinarySubtype,
+    spec::{BinarySubtype, ElementType},
+    to_vec,
     Binary,
     Bson,
+    DecoderError,
+    Document,
+    DocumentEncoder,
+    DocumentReader,
+    EncoderError,
     JavaScriptCodeWithScope,
+    RawDecoder,
+    RawDocument,
     Regex,
     TimeStamp,
+    UtcDateTime,
 };
 use byteorder::{LittleEndian, WriteBytesExt};
 use chrono::{offset::TimeZone, Utc};
+use serde::Deserialize;
 use std::io::{Cursor, Write};
 
 #[test]
@@ -59,7 +74,7 @@ fn test_encode_decode_utf8_string_invalid() {
     let bytes = b"\x80\xae".to_vec();
     let src = unsafe { String::from_utf8_unchecked(bytes) };
 
-    let doc = doc! { "key": src };
+    let doc = doc! { "key": src.clone() };
 
     let mut buf = Vec::new();
     encode_document(&mut buf, &doc).unwrap();
@@ -67,6 +82,23 @@ fn test_encode_decode_utf8_string_invalid() {
     let expected = doc! { "key": "��" };
     let decoded = decode_document_utf8_lossy(&mut Cursor::new(buf)).unwrap();
     assert_eq!(decoded, expected);
+
+    // Lossy decoding applies at every depth, including code with scope.
+    let doc = doc! {
+        "doc": { "key": src.clone() },
+        "array": [{ "key": src.clone() }],
+        "code": (JavaScriptCodeWithScope::new("x", doc! { "key": src })),
+    };
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+
+    let expected = doc! {
+        "doc": { "key": "��" },
+        "array": [{ "key": "��" }],
+        "code": (JavaScriptCodeWithScope::new("x", doc! { "key": "��" })),
+    };
+    let decoded = decode_document_utf8_lossy(&mut Cursor::new(buf)).unwrap();
+    assert_eq!(decoded, expected);
 }
 
 #[test]
@@ -195,6 +227,42 @@ fn test_encode_decode_javascript_code_with_scope() {
     assert_eq!(decoded, doc);
 }
 
+#[test]
+fn test_encode_decode_javascript_code_with_nonempty_scope() {
+    let src = Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+        code: "function() { return x + y.z; }".to_owned(),
+        scope: doc! { "x": 1, "y": { "z": "foo" } },
+    });
+
+    let doc = doc! { "key": src, "after": true };
+
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+
+    let decoded = decode_document(&mut Cursor::new(buf)).unwrap();
+    assert_eq!(decoded, doc);
+}
+
+#[test]
+fn test_decode_javascript_code_with_scope_bad_length() {
+    let src = Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+        code: "1".to_owned(),
+        scope: doc! {},
+    });
+
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc! { "key": src }).unwrap();
+
+    // The code with scope length directly follows the element type and the "key" cstring.
+    let mut too_long = buf.clone();
+    too_long[9] += 1;
+    assert!(decode_document(&mut Cursor::new(too_long)).is_err());
+
+    let mut too_short = buf;
+    too_short[9] -= 1;
+    assert!(decode_document(&mut Cursor::new(too_short)).is_err());
+}
+
 #[test]
 fn test_encode_decode_i32() {
     let src = 100i32;
@@ -433,6 +501,25 @@ fn test_encode_decode_max_key() {
     assert_eq!(decoded, doc);
 }
 
+#[test]
+fn test_encode_decode_min_and_max_key() {
+    let doc = doc! {
+        "min": Bson::MinKey,
+        "max": Bson::MaxKey,
+        "arr": [Bson::MaxKey, Bson::MinKey, 1],
+        "after": "value",
+    };
+
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+
+    let decoded = decode_document(&mut Cursor::new(buf)).unwrap();
+    assert_eq!(decoded, doc);
+
+    let deserialized: Bson = bson::from_bson(Bson::Document(decoded)).unwrap();
+    assert_eq!(deserialized, Bson::Document(doc));
+}
+
 #[test]
 fn test_encode_decode_db_pointer() {
     let src = Bson::from_extended_document(doc! {
@@ -456,3 +543,536 @@ fn test_encode_decode_db_pointer() {
     let decoded = decode_document(&mut Cursor::new(buf)).unwrap();
     assert_eq!(decoded, doc);
 }
+
+#[test]
+fn test_document_encoder() {
+    let mut encoder = DocumentEncoder::new(Cursor::new(Vec::new())).unwrap();
+    encoder.write_i32("i32", 1).unwrap();
+    encoder.write_i64("i64", 2).unwrap();
+    encoder.write_f64("f64", 3.5).unwrap();
+    encoder.write_bool("bool", true).unwrap();
+    encoder.write_str("str", "foo").unwrap();
+    encoder
+        .write_document("doc", |doc| {
+            doc.write_str("inner", "bar")?;
+            doc.write_document("empty", |_| Ok(()))?;
+            doc.write_bson("arr", &Bson::Array(vec![Bson::I32(1), Bson::Null]))
+        })
+        .unwrap();
+    encoder.write_bson("null", &Bson::Null).unwrap();
+    let buf = encoder.finish().unwrap().into_inner();
+
+    let expected = doc! {
+        "i32": 1i32,
+        "i64": 2i64,
+        "f64": 3.5,
+        "bool": true,
+        "str": "foo",
+        "doc": {
+            "inner": "bar",
+            "empty": {},
+            "arr": [1i32, Bson::Null],
+        },
+        "null": Bson::Null,
+    };
+
+    let mut expected_buf = Vec::new();
+    encode_document(&mut expected_buf, &expected).unwrap();
+    assert_eq!(buf, expected_buf);
+
+    let decoded = decode_document(&mut Cursor::new(buf)).unwrap();
+    assert_eq!(decoded, expected);
+}
+
+#[test]
+fn test_decode_truncated_document() {
+    let doc = doc! {
+        "str": "foo",
+        "arr": [1i32, { "x": 2.5 }],
+        "code": Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+            code: "x".to_owned(),
+            scope: doc! { "x": 1i64 },
+        }),
+        "bin": Binary { subtype: BinarySubtype::Generic, bytes: vec![1, 2, 3] },
+    };
+
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+
+    for len in 0..buf.len() {
+        assert!(
+            decode_document(&mut Cursor::new(&buf[..len])).is_err(),
+            "decoding {} of {} bytes should fail",
+            len,
+            buf.len()
+        );
+    }
+}
+
+#[test]
+fn test_decode_invalid_element_type() {
+    // { "key": <type 0x42> 1i32 }
+    let buffer = [14, 0, 0, 0, 0x42, 107, 101, 121, 0, 1, 0, 0, 0, 0];
+    let err = decode_document(&mut Cursor::new(&buffer[..])).unwrap_err();
+    assert_matches!(err, DecoderError::UnrecognizedElementType(0x42));
+    assert_eq!(err.to_string(), "unrecognized element type `0x42`");
+
+    // { "arr": [<type 0x20> 1i32] }
+    let buffer = [
+        22, 0, 0, 0, 4, 97, 114, 114, 0, 12, 0, 0, 0, 0x20, 48, 0, 1, 0, 0, 0, 0, 0,
+    ];
+    assert_matches!(
+        decode_document(&mut Cursor::new(&buffer[..])),
+        Err(DecoderError::UnrecognizedElementType(0x20))
+    );
+}
+
+#[test]
+fn test_decode_binary_bad_length() {
+    // { "b": BinData(0, 0x010203) } with the binary length replaced
+    let with_length = |len: i32| {
+        let mut buffer = vec![16, 0, 0, 0, 5, 98, 0];
+        buffer.write_i32::<LittleEndian>(len).unwrap();
+        buffer.extend_from_slice(&[0, 1, 2, 3, 0]);
+        buffer
+    };
+
+    assert!(decode_document(&mut Cursor::new(with_length(3))).is_ok());
+
+    assert_matches!(
+        decode_document(&mut Cursor::new(with_length(-1))),
+        Err(DecoderError::InvalidLength(..))
+    );
+    assert_matches!(
+        decode_document(&mut Cursor::new(with_length(100))),
+        Err(DecoderError::InvalidLength(100, _))
+    );
+    assert_matches!(
+        decode_document(&mut Cursor::new(with_length(i32::MAX))),
+        Err(DecoderError::InvalidLength(..))
+    );
+}
+
+#[test]
+fn test_encode_decode_empty_uuid_binary() {
+    let src = Binary {
+        subtype: BinarySubtype::Uuid,
+        bytes: Vec::new(),
+    };
+    let doc = doc! { "key": src.clone() };
+
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+    assert_eq!(buf, [15, 0, 0, 0, 5, 107, 101, 121, 0, 0, 0, 0, 0, 4, 0]);
+
+    let decoded = decode_document(&mut Cursor::new(&buf)).unwrap();
+    assert_eq!(decoded, doc);
+
+    let (raw, _): (bson::Document, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(raw, doc);
+
+    let json: serde_json::Value = Bson::Binary(src.clone()).into();
+    assert_eq!(Bson::from(json), Bson::Binary(src));
+}
+
+#[test]
+fn test_encode_document_size_limit() {
+    const MAX_SIZE: usize = 16 * 1024 * 1024;
+
+    // { "b": BinData(0, ...) } takes 13 bytes besides the binary payload.
+    let with_payload = |len: usize| {
+        doc! {
+            "b": Binary { subtype: BinarySubtype::Generic, bytes: vec![0; len] },
+        }
+    };
+
+    let at_limit = with_payload(MAX_SIZE - 13);
+    assert!(!at_limit.exceeds_size_limit());
+
+    let mut buf = Vec::new();
+    encode_document_checked(&mut buf, &at_limit).unwrap();
+    assert_eq!(buf.len(), MAX_SIZE);
+
+    let over_limit = with_payload(MAX_SIZE - 12);
+    assert!(over_limit.exceeds_size_limit());
+
+    let mut buf = Vec::new();
+    assert_matches!(
+        encode_document_checked(&mut buf, &over_limit),
+        Err(EncoderError::DocumentTooLarge(size)) if size == MAX_SIZE + 1
+    );
+    assert!(buf.is_empty());
+
+    // The unchecked encoder still accepts it.
+    encode_document(&mut buf, &over_limit).unwrap();
+    assert_eq!(buf.len(), MAX_SIZE + 1);
+}
+
+#[cfg(not(feature = "decimal128"))]
+#[test]
+fn test_decimal128_without_feature() {
+    use bson::decimal128::Decimal128;
+
+    // { "d": Decimal128("0") }
+    let mut buffer = vec![24, 0, 0, 0, 0x13, b'd', 0];
+    buffer.extend_from_slice(&[0; 14]);
+    buffer.extend_from_slice(&[0x40, 0x30, 0]);
+
+    let doc = decode_document(&mut Cursor::new(&buffer)).unwrap();
+    assert_eq!(
+        doc.get("d").unwrap().to_extended_json().to_string(),
+        r#"{"$numberDecimal":"0"}"#
+    );
+    assert_eq!(bson::from_slice::<Document>(&buffer).unwrap().0, doc);
+    assert_eq!(
+        bson::from_bson::<Document>(Bson::Document(doc.clone())).unwrap(),
+        doc
+    );
+    assert_eq!(
+        bson::from_bson::<Document>(bson::to_bson(&doc).unwrap()).unwrap(),
+        doc
+    );
+
+    let mut encoded = Vec::new();
+    encode_document(&mut encoded, &doc).unwrap();
+    assert_eq!(encoded, buffer);
+
+    for &(s, high, low) in &[
+        ("1.5", 0x303e_0000_0000_0000, 15),
+        ("-0.001", 0xb03a_0000_0000_0000, 1),
+        (
+            "1.000000000000000000000000000000000E+6144",
+            0x5ffe_314d_c644_8d93,
+            0x38c1_5b0a_0000_0000,
+        ),
+        ("1E-6176", 0x0000_0000_0000_0000, 1),
+        ("0E+6111", 0x5ffe_0000_0000_0000, 0),
+        ("1.0E+6112", 0x5ffe_0000_0000_0000, 10),
+        ("-Infinity", 0xf800_0000_0000_0000, 0),
+        ("NaN", 0x7c00_0000_0000_0000, 0),
+    ] {
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&u64::to_le_bytes(low));
+        bytes[8..].copy_from_slice(&u64::to_le_bytes(high));
+        let dec = Decimal128::from_bytes(bytes);
+        assert_eq!(dec.to_string(), s);
+        assert_eq!(s.parse::<Decimal128>().unwrap(), dec);
+
+        let json = Bson::Decimal128(dec.clone()).to_extended_json();
+        assert_eq!(json, serde_json::json!({ "$numberDecimal": s }));
+        assert_eq!(
+            Bson::from_extended_json_str(&json.to_string()).unwrap(),
+            Bson::Decimal128(dec)
+        );
+    }
+
+    for s in &[
+        "", "-", ".", "1.2.3", "1E", "1E+", "abc", "1E+6145", "1E-6177",
+    ] {
+        assert_eq!(s.parse::<Decimal128>(), Err(()), "{:?}", s);
+    }
+}
+
+#[test]
+fn test_encode_decode_timestamp_byte_order() {
+    // The increment occupies the low four bytes and the time the high four, both little-endian.
+    let src = Bson::TimeStamp(TimeStamp {
+        time: 0x0102_0304,
+        increment: 0x0506_0708,
+    });
+    let dst = vec![
+        18, 0, 0, 0, 17, 107, 101, 121, 0, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, 0,
+    ];
+
+    let doc = doc! { "key": src };
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+    assert_eq!(buf, dst);
+    assert_eq!(decode_document(&mut Cursor::new(buf)).unwrap(), doc);
+
+    let edges = [0, 1, 0x7FFF_FFFF, 0x8000_0000, 0xDEAD_BEEF, u32::MAX];
+    for &time in &edges {
+        for &increment in &edges {
+            let doc = doc! { "ts": Bson::TimeStamp(TimeStamp { time, increment }) };
+
+            let mut buf = Vec::new();
+            encode_document(&mut buf, &doc).unwrap();
+            assert_eq!(decode_document(&mut Cursor::new(buf)).unwrap(), doc);
+        }
+    }
+}
+
+#[test]
+fn test_encode_decode_utc_date_time_before_epoch() {
+    for &millis in &[-1, -999, -1_000, -1_001, -86_400_000 - 123] {
+        let date = UtcDateTime::from_millis(millis);
+        let doc = doc! { "date": Bson::UtcDatetime(*date) };
+
+        let mut buf = Vec::new();
+        encode_document(&mut buf, &doc).unwrap();
+        assert_eq!(&buf[10..18], &millis.to_le_bytes());
+
+        let decoded = decode_document(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(decoded, doc);
+        assert_eq!(
+            UtcDateTime::from(*decoded.get_utc_datetime("date").unwrap()).to_millis(),
+            millis
+        );
+    }
+}
+
+#[test]
+fn test_document_reader() {
+    let docs = vec![
+        doc! { "x": 1 },
+        doc! { "y": "two", "nested": { "z": [3] } },
+        doc! {},
+    ];
+
+    let mut buf = Vec::new();
+    for doc in &docs {
+        encode_document(&mut buf, doc).unwrap();
+    }
+
+    let read: Vec<_> = DocumentReader::new(Cursor::new(&buf))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, docs);
+
+    // A stream cut off partway through a document yields an error and then stops.
+    let mut reader = DocumentReader::new(&buf[..buf.len() - 1]);
+    assert_eq!(reader.next().unwrap().unwrap(), docs[0]);
+    assert_eq!(reader.next().unwrap().unwrap(), docs[1]);
+    assert_matches!(reader.next(), Some(Err(DecoderError::EndOfStream)));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_document_reader_max_size() {
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc! { "b": (Binary::generic(vec![0; 64])) }).unwrap();
+
+    let mut reader = DocumentReader::with_max_size(&buf[..], buf.len());
+    assert!(reader.next().unwrap().is_ok());
+
+    let mut reader = DocumentReader::with_max_size(&buf[..], 32);
+    assert_matches!(reader.next(), Some(Err(DecoderError::InvalidLength(..))));
+    assert!(reader.next().is_none());
+
+    // { "b": BinData(0, ...) } claiming a 2GB binary value, inside a document claiming the same.
+    let mut bomb = Vec::new();
+    bomb.write_i32::<LittleEndian>(i32::MAX).unwrap();
+    bomb.extend_from_slice(&[5, 98, 0]);
+    bomb.write_i32::<LittleEndian>(i32::MAX - 12).unwrap();
+    bomb.extend_from_slice(&[0, 1, 2, 3, 0]);
+
+    let mut reader = DocumentReader::new(&bomb[..]);
+    assert_matches!(reader.next(), Some(Err(DecoderError::InvalidLength(..))));
+
+    // Only the binary length is bogus, so the limit on the value itself rejects it.
+    bomb[..4].copy_from_slice(&16_i32.to_le_bytes());
+    assert_matches!(
+        decode_document(&mut Cursor::new(&bomb)),
+        Err(DecoderError::InvalidLength(..))
+    );
+
+    // The same limits apply to decode_document and from_slice, and can be changed on both.
+    assert_matches!(
+        decode_document_with_max_size(&mut Cursor::new(&buf), 32),
+        Err(DecoderError::InvalidLength(..))
+    );
+    assert!(decode_document_with_max_size(&mut Cursor::new(&buf), buf.len()).is_ok());
+    assert!(from_slice::<Document>(&buf).is_ok());
+    assert_matches!(
+        Document::deserialize(&mut RawDecoder::with_max_size(&buf, 32)),
+        Err(DecoderError::InvalidLength(..))
+    );
+
+    let mut bomb = Vec::new();
+    bomb.write_i32::<LittleEndian>(32).unwrap();
+    bomb.extend_from_slice(&[5, 98, 0]);
+    bomb.write_i32::<LittleEndian>(i32::MAX - 12).unwrap();
+    bomb.extend_from_slice(&[0; 21]);
+    assert_matches!(
+        from_slice::<Document>(&bomb),
+        Err(DecoderError::InvalidLength(..))
+    );
+
+    // A string claiming 2GB is not allocated up front, and fails once the input runs out.
+    let mut bomb = vec![16, 0, 0, 0, 2, 115, 0];
+    bomb.write_i32::<LittleEndian>(i32::MAX).unwrap();
+    bomb.extend_from_slice(&[97, 98, 0, 0]);
+    assert!(decode_document(&mut Cursor::new(&bomb)).is_err());
+}
+
+#[test]
+fn test_code_with_scope_max_size() {
+    let doc = doc! { "c": (JavaScriptCodeWithScope::new("x", doc! { "a": 1 })) };
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+    assert_eq!(
+        decode_document_with_max_size(&mut Cursor::new(&buf), 64).unwrap(),
+        doc
+    );
+
+    // The same document with the code with scope claiming 16MB, under a limit of 64 bytes.
+    let mut bomb = buf.clone();
+    bomb[7..11].copy_from_slice(&(16 * 1024 * 1024_i32).to_le_bytes());
+
+    let is_limit_error = |result: Result<_, DecoderError>| match result {
+        Err(DecoderError::InvalidLength(_, msg)) => msg.contains("exceeds the maximum of 64"),
+        _ => false,
+    };
+    assert!(is_limit_error(
+        decode_document_with_max_size(&mut Cursor::new(&bomb), 64).map(|_| ())
+    ));
+    assert!(is_limit_error(
+        DocumentReader::with_max_size(&bomb[..], 64)
+            .next()
+            .unwrap()
+            .map(|_| ())
+    ));
+    assert!(is_limit_error(
+        Document::deserialize(&mut RawDecoder::with_max_size(&bomb, 64)).map(|_| ())
+    ));
+
+    // Skipping the value checks the limit too.
+    #[derive(serde_derive::Deserialize, Debug)]
+    struct Empty {}
+    assert!(is_limit_error(
+        Empty::deserialize(&mut RawDecoder::with_max_size(&bomb, 64)).map(|_| ())
+    ));
+}
+
+#[test]
+fn test_raw_document() {
+    let oid = ObjectId::with_string("53e37d08776f724e42000000").unwrap();
+    let doc = doc! {
+        "_id": oid.clone(),
+        "name": "bson",
+        "bad": "xx",
+        "tags": ["a", "b"],
+        "meta": { "stars": 10_i64, "public": true },
+        "score": 1.5,
+    };
+    let mut bytes = to_vec(&doc).unwrap();
+
+    // Corrupt the "bad" string into invalid UTF-8. Fields are skipped without being decoded,
+    // so this only matters when the value itself is read.
+    let pos = bytes.windows(2).position(|w| w == b"xx").unwrap();
+    bytes[pos] = 0xFF;
+    assert!(decode_document(&mut Cursor::new(&bytes)).is_err());
+
+    let raw = RawDocument::new(&bytes).unwrap();
+    assert_eq!(raw.as_bytes().len(), bytes.len());
+
+    let keys: Vec<_> = raw.keys().collect::<Result<_, _>>().unwrap();
+    assert_eq!(keys, ["_id", "name", "bad", "tags", "meta", "score"]);
+
+    assert_eq!(raw.get("_id").unwrap().unwrap().as_object_id(), Some(oid));
+    assert_eq!(raw.get("name").unwrap().unwrap().as_str(), Some("bson"));
+    assert_eq!(raw.get("score").unwrap().unwrap().as_f64(), Some(1.5));
+    assert!(raw.get("missing").unwrap().is_none());
+
+    let bad = raw.get("bad").unwrap().unwrap();
+    assert_eq!(bad.element_type(), ElementType::Utf8String);
+    assert_eq!(bad.as_str(), None);
+    assert!(bad.to_bson().is_err());
+
+    let meta = raw.get("meta").unwrap().unwrap().as_document().unwrap();
+    assert_eq!(meta.get("stars").unwrap().unwrap().as_i64(), Some(10));
+    assert_eq!(meta.get("public").unwrap().unwrap().as_bool(), Some(true));
+    assert_eq!(
+        meta.to_document().unwrap(),
+        doc! { "stars": 10_i64, "public": true }
+    );
+
+    let tags = raw.get("tags").unwrap().unwrap();
+    assert!(tags.as_document().is_none());
+    assert_eq!(tags.to_bson().unwrap(), Bson::from(vec!["a", "b"]));
+    let tags = tags.as_array().unwrap();
+    assert_eq!(tags.get("1").unwrap().unwrap().as_str(), Some("b"));
+
+    // A malformed element is reported when the scan reaches it.
+    let truncated = [16, 0, 0, 0, 16, b'a', 0, 1, 0, 0, 0, 2, b'b', 0, 9, 0];
+    let raw = RawDocument::new(&truncated).unwrap();
+    assert_eq!(raw.get("a").unwrap().unwrap().as_i32(), Some(1));
+    assert_matches!(raw.get("b"), Err(DecoderError::EndOfStream));
+
+    assert_matches!(
+        RawDocument::new(&[4, 0, 0, 0]),
+        Err(DecoderError::InvalidLength(..))
+    );
+}
+
+#[test]
+fn test_to_vec() {
+    let doc = doc! {
+        "a": 1,
+        "b": "two",
+        "c": [3.0, { "d": Bson::Null }],
+        "e": (Binary::generic(vec![4; 10])),
+        "f": (Regex::new("^g", "i")),
+    };
+
+    let buf = to_vec(&doc).unwrap();
+    assert_eq!(buf.len(), buf.capacity());
+    assert_eq!(decode_document(&mut Cursor::new(&buf)).unwrap(), doc);
+
+    let mut expected = Vec::new();
+    encode_document(&mut expected, &doc).unwrap();
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn test_encoded_len() {
+    let doc = doc! {
+        "double": 1.5,
+        "string": "hello",
+        "array": [1, "two", { "three": 3_i64 }],
+        "binary": (Binary::generic(vec![1, 2, 3])),
+        "oid": ObjectId::with_string("507f1f77bcf86cd799439011").unwrap(),
+        "bool": true,
+        "null": Bson::Null,
+        "regex": (Regex::new("^a", "i")),
+        "code_with_scope": (JavaScriptCodeWithScope::new("x", doc! { "x": 1 })),
+        "timestamp": (Bson::TimeStamp(TimeStamp::new(1, 2))),
+        "max": Bson::MaxKey,
+    };
+
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+    assert_eq!(Bson::Document(doc.clone()).encoded_len(), buf.len());
+
+    for (key, value) in doc.iter() {
+        let mut buf = Vec::new();
+        encode_document(&mut buf, &doc! { key.clone(): value.clone() }).unwrap();
+        // length, element type, key, null terminators
+        assert_eq!(value.encoded_len(), buf.len() - 4 - 1 - key.len() - 1 - 1);
+    }
+}
+
+#[test]
+fn test_from_reader_to_json() {
+    let id = ObjectId::with_string("507f1f77bcf86cd799439011").unwrap();
+    let doc = doc! { "_id": id.clone(), "n": 1, "tags": ["a"] };
+
+    let mut buf = Vec::new();
+    encode_document(&mut buf, &doc).unwrap();
+
+    let json = from_reader_to_json(Cursor::new(buf)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "_id": { "$oid": "507f1f77bcf86cd799439011" },
+            "n": 1,
+            "tags": ["a"],
+        })
+    );
+    assert_eq!(
+        Bson::from(json),
+        Bson::Document(doc! { "_id": id, "n": 1_i64, "tags": ["a"] })
+    );
+
+    assert!(from_reader_to_json(Cursor::new(vec![5, 0, 0])).is_err());
+}
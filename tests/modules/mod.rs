@@ -4,3 +4,4 @@ mod macros;
 mod oid;
 mod ordered;
 mod ser;
+mod spec;
@@ -1,4 +1,7 @@
+use std::{collections::BTreeSet, thread, time::Duration};
+
 use bson::oid::ObjectId;
+use chrono::{offset::TimeZone, Utc};
 
 #[test]
 fn string_oid() {
@@ -42,3 +45,133 @@ fn counter_increasing() {
     let oid2_bytes = ObjectId::new().bytes();
     assert!(oid1_bytes[11] < oid2_bytes[11]);
 }
+
+#[test]
+fn counter_and_process_id() {
+    let oid = ObjectId::with_string("5e5f0a000102030405abcdef").unwrap();
+    assert_eq!(oid.process_id(), [0x01, 0x02, 0x03, 0x04, 0x05]);
+    assert_eq!(oid.counter(), 0x00ab_cdef);
+
+    let oid1 = ObjectId::new();
+    let oid2 = ObjectId::new();
+    assert!(oid1.counter() < oid2.counter());
+}
+
+#[test]
+fn parse_bytes() {
+    let bytes = ObjectId::parse_bytes("53e37d08776f724e42000000").unwrap();
+    assert_eq!(
+        bytes,
+        [0x53, 0xe3, 0x7d, 0x08, 0x77, 0x6f, 0x72, 0x4e, 0x42, 0x00, 0x00, 0x00]
+    );
+
+    assert!(ObjectId::parse_bytes("53e37d08776f724e4200000").is_err());
+    assert!(ObjectId::parse_bytes("53e37d08776f724e4200000g").is_err());
+}
+
+#[test]
+fn sort_by_creation_time() {
+    let mut ids = [
+        ObjectId::with_string("5e5f0a000000000000000003").unwrap(),
+        ObjectId::with_string("5e5f09ff0000000000000009").unwrap(),
+        ObjectId::with_string("5e5f0a000000000000000001").unwrap(),
+        ObjectId::with_string("4e5f0a00ffffffffffffffff").unwrap(),
+    ];
+    ids.sort();
+
+    let hex: Vec<_> = ids.iter().map(ObjectId::to_hex).collect();
+    assert_eq!(
+        hex,
+        [
+            "4e5f0a00ffffffffffffffff",
+            "5e5f09ff0000000000000009",
+            "5e5f0a000000000000000001",
+            "5e5f0a000000000000000003",
+        ]
+    );
+    assert_eq!(ids.iter().min(), ids.first());
+    assert_eq!(ids.iter().max(), ids.last());
+}
+
+#[test]
+fn later_id_sorts_after() {
+    // Ids from the same second may order either way, since the bytes after the timestamp are
+    // random, so wait for the next second.
+    let earlier = ObjectId::new();
+    let mut later = ObjectId::new();
+    while later.time() == earlier.time() {
+        thread::sleep(Duration::from_millis(50));
+        later = ObjectId::new();
+    }
+    assert!(later > earlier);
+
+    let set: BTreeSet<_> = vec![later.clone(), earlier.clone()].into_iter().collect();
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), [earlier, later]);
+}
+
+#[test]
+fn from_timestamp_boundary() {
+    // 2020-03-04T01:02:03Z
+    let time = Utc.timestamp_opt(1_583_283_723, 0).unwrap();
+    let boundary = ObjectId::from_timestamp(time);
+
+    assert_eq!(boundary.to_hex(), "5e5efe0b0000000000000000");
+
+    let before = ObjectId::with_string("5e5efe0affffffffffffffff").unwrap();
+    let at = ObjectId::with_string("5e5efe0b0000000000000001").unwrap();
+    assert!(before < boundary);
+    assert!(boundary < at);
+}
+
+#[test]
+fn from_timestamp_clamps_to_u32_range() {
+    let epoch = Utc.timestamp_opt(0, 0).unwrap();
+    assert_eq!(ObjectId::from_timestamp(epoch).time(), 0);
+    let before_epoch = Utc.timestamp_opt(-1, 0).unwrap();
+    assert_eq!(ObjectId::from_timestamp(before_epoch).time(), 0);
+    let long_before_epoch = Utc.timestamp_opt(-(1 << 40), 0).unwrap();
+    assert_eq!(ObjectId::from_timestamp(long_before_epoch).time(), 0);
+
+    // 2106-02-07T06:28:15Z is the latest representable time.
+    let latest = Utc.timestamp_opt(i64::from(u32::MAX), 0).unwrap();
+    assert_eq!(ObjectId::from_timestamp(latest).time(), u32::MAX);
+    let after_latest = Utc.timestamp_opt(i64::from(u32::MAX) + 1, 0).unwrap();
+    assert_eq!(ObjectId::from_timestamp(after_latest).time(), u32::MAX);
+    let long_after_latest = Utc.timestamp_opt(1 << 40, 0).unwrap();
+    assert_eq!(ObjectId::from_timestamp(long_after_latest).time(), u32::MAX);
+}
+
+#[test]
+fn timestamp() {
+    let oid = ObjectId::with_string("5e5efe0b0a0b0c0d0e0f1011").unwrap();
+    assert_eq!(oid.time(), 0x5e5e_fe0b);
+    assert_eq!(
+        oid.timestamp(),
+        Utc.timestamp_opt(1_583_283_723, 0).unwrap()
+    );
+
+    let oid = ObjectId::with_bytes([0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(oid.time(), u32::MAX);
+    assert_eq!(
+        oid.timestamp(),
+        Utc.timestamp_opt(i64::from(u32::MAX), 0).unwrap()
+    );
+
+    let time = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+    assert_eq!(ObjectId::from_timestamp(time).timestamp(), time);
+}
+
+#[test]
+fn from_str() {
+    let oid: ObjectId = "541b1a00e8a23afa832b218e".parse().unwrap();
+    assert_eq!(
+        oid,
+        ObjectId::with_string("541b1a00e8a23afa832b218e").unwrap()
+    );
+
+    match "541b1a00e8a23afa832b21".parse::<ObjectId>() {
+        Err(bson::oid::Error::ArgumentError(_)) => {}
+        other => panic!("expected argument error, got {:?}", other),
+    }
+    assert!("not hex at all, but 24 ch".parse::<ObjectId>().is_err());
+}
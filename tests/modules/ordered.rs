@@ -3,14 +3,15 @@ use bson::decimal128::Decimal128;
 use bson::{
     doc,
     oid::ObjectId,
-    spec::BinarySubtype,
+    spec::{BinarySubtype, ElementType},
     Binary,
     Bson,
     Document,
+    DocumentBuilder,
     TimeStamp,
     ValueAccessError,
 };
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 
 #[test]
 fn ordered_insert() {
@@ -52,6 +53,14 @@ fn test_decimal128(doc: &mut Document) {
     doc.insert("decimal128".to_string(), Bson::Decimal128(dec.clone()));
     assert_eq!(Some(&Bson::Decimal128(dec.clone())), doc.get("decimal128"));
     assert_eq!(Ok(&dec), doc.get_decimal128("decimal128"));
+    assert_eq!(
+        Err(ValueAccessError::UnexpectedType),
+        doc.get_decimal128("datetime")
+    );
+    assert_eq!(
+        Err(ValueAccessError::NotPresent),
+        doc.get_decimal128("missing")
+    );
 }
 
 #[cfg(not(feature = "decimal128"))]
@@ -157,6 +166,62 @@ fn test_getters() {
     assert_eq!(Ok(&binary), doc.get_binary_generic("binary"));
 }
 
+#[test]
+fn binary_and_time_stamp_getters() {
+    let uuid = Binary {
+        subtype: BinarySubtype::Uuid,
+        bytes: vec![7; 16],
+    };
+    let timestamp = TimeStamp {
+        time: 12,
+        increment: 3,
+    };
+    let mut doc = doc! {
+        "uuid": uuid.clone(),
+        "ts": timestamp,
+        "string": "not binary",
+    };
+
+    assert_eq!(Ok(&uuid), doc.get_binary("uuid"));
+    assert_eq!(
+        Err(ValueAccessError::UnexpectedType),
+        doc.get_binary("string")
+    );
+    assert_eq!(Err(ValueAccessError::NotPresent), doc.get_binary("missing"));
+
+    doc.get_binary_mut("uuid").unwrap().bytes.push(8);
+    assert_eq!(17, doc.get_binary("uuid").unwrap().bytes.len());
+
+    assert_eq!(Ok(timestamp), doc.get_time_stamp("ts"));
+    assert_eq!(
+        Err(ValueAccessError::UnexpectedType),
+        doc.get_time_stamp("uuid")
+    );
+    assert_eq!(
+        Err(ValueAccessError::NotPresent),
+        doc.get_time_stamp("missing")
+    );
+}
+
+#[test]
+fn utc_datetime_getter() {
+    let datetime = Utc.timestamp_millis_opt(1_590_972_160_292).unwrap();
+    let mut doc = doc! { "date": datetime, "millis": 1_590_972_160_292_i64 };
+
+    assert_eq!(Ok(&datetime), doc.get_utc_datetime("date"));
+    assert_eq!(
+        Err(ValueAccessError::UnexpectedType),
+        doc.get_utc_datetime("millis")
+    );
+    assert_eq!(
+        Err(ValueAccessError::NotPresent),
+        doc.get_utc_datetime("missing")
+    );
+
+    *doc.get_utc_datetime_mut("date").unwrap() = Utc.timestamp_millis_opt(0).unwrap();
+    assert_eq!(0, doc.get_utc_datetime("date").unwrap().timestamp_millis());
+}
+
 #[test]
 fn remove() {
     let mut doc = Document::new();
@@ -212,3 +277,331 @@ fn entry() {
         },
     );
 }
+
+#[test]
+fn entry_counter() {
+    fn increment(doc: &mut Document, key: &str) {
+        doc.entry(key)
+            .and_modify(|count| {
+                if let Bson::I32(ref mut count) = *count {
+                    *count += 1;
+                }
+            })
+            .or_insert(Bson::I32(1));
+    }
+
+    let mut counts = doc! { "existing": 5 };
+    increment(&mut counts, "new");
+    increment(&mut counts, "existing");
+    increment(&mut counts, "new");
+    increment(&mut counts, "other");
+
+    // New keys are appended in the order they were first inserted.
+    assert_eq!(counts, doc! { "existing": 6, "new": 2, "other": 1 });
+    assert_eq!(
+        counts.keys().collect::<Vec<_>>(),
+        ["existing", "new", "other"]
+    );
+}
+
+#[test]
+fn type_histogram() {
+    let doc = doc! {
+        "a": 1i32,
+        "b": "foo",
+        "c": {
+            "d": 2i32,
+            "e": [1i64, "bar", { "f": true }],
+        },
+    };
+
+    let shallow = doc.type_histogram(false);
+    assert_eq!(shallow.len(), 3);
+    assert_eq!(shallow[&ElementType::Integer32Bit], 1);
+    assert_eq!(shallow[&ElementType::Utf8String], 1);
+    assert_eq!(shallow[&ElementType::EmbeddedDocument], 1);
+
+    let deep = doc.type_histogram(true);
+    assert_eq!(deep.len(), 6);
+    assert_eq!(deep[&ElementType::Integer32Bit], 2);
+    assert_eq!(deep[&ElementType::Integer64Bit], 1);
+    assert_eq!(deep[&ElementType::Utf8String], 2);
+    assert_eq!(deep[&ElementType::EmbeddedDocument], 2);
+    assert_eq!(deep[&ElementType::Array], 1);
+    assert_eq!(deep[&ElementType::Boolean], 1);
+}
+
+#[test]
+fn vec_roundtrip() {
+    let doc = doc! {
+        "z": 1i32,
+        "a": "foo",
+        "m": { "nested": [1i32, 2i32] },
+    };
+
+    let mut vec = doc.clone().into_vec();
+    assert_eq!(
+        vec,
+        vec![
+            ("z".to_owned(), Bson::I32(1)),
+            ("a".to_owned(), Bson::String("foo".to_owned())),
+            (
+                "m".to_owned(),
+                Bson::Document(doc! { "nested": [1i32, 2i32] })
+            ),
+        ]
+    );
+    assert_eq!(Document::from_vec(vec.clone()), doc);
+
+    vec.sort_by(|a, b| a.0.cmp(&b.0));
+    let sorted = Document::from_vec(vec);
+    let keys: Vec<_> = sorted.keys().cloned().collect();
+    assert_eq!(keys, vec!["a", "m", "z"]);
+    assert_eq!(sorted.get("m"), doc.get("m"));
+}
+
+#[test]
+fn try_into_array() {
+    let doc = doc! { "0": "a", "1": 2i32, "2": { "x": true } };
+    assert_eq!(
+        doc.try_into_array(),
+        Ok(vec![
+            Bson::String("a".to_owned()),
+            Bson::I32(2),
+            Bson::Document(doc! { "x": true }),
+        ])
+    );
+
+    let unordered = doc! { "1": "b", "0": "a" };
+    assert_eq!(
+        unordered.try_into_array(),
+        Ok(vec![
+            Bson::String("a".to_owned()),
+            Bson::String("b".to_owned())
+        ])
+    );
+
+    assert_eq!(Document::new().try_into_array(), Ok(vec![]));
+
+    let gapped = doc! { "0": "a", "2": "c" };
+    assert_eq!(gapped.clone().try_into_array(), Err(gapped));
+
+    let padded = doc! { "0": "a", "01": "b" };
+    assert_eq!(padded.clone().try_into_array(), Err(padded));
+
+    let named = doc! { "0": "a", "name": "b" };
+    assert_eq!(named.clone().try_into_array(), Err(named));
+}
+
+#[test]
+fn get_time_stamp_from_oplog_entry() {
+    let entry = doc! {
+        "ts": Bson::TimeStamp(TimeStamp { time: 1_583_283_723, increment: 7 }),
+        "t": 1_i64,
+        "op": "i",
+        "ns": "db.coll",
+        "o": { "_id": 1 },
+    };
+
+    assert_eq!(
+        Ok(TimeStamp {
+            time: 1_583_283_723,
+            increment: 7,
+        }),
+        entry.get_time_stamp("ts")
+    );
+    assert_eq!(
+        Err(ValueAccessError::UnexpectedType),
+        entry.get_time_stamp("t")
+    );
+    assert_eq!(
+        Err(ValueAccessError::UnexpectedType),
+        entry.get_time_stamp("op")
+    );
+    assert_eq!(
+        Err(ValueAccessError::NotPresent),
+        entry.get_time_stamp("wall")
+    );
+}
+
+#[test]
+fn retain_and_remove_matching() {
+    let mut doc = doc! {
+        "user_name": "alice",
+        "password": "hunter2",
+        "user_id": 7,
+        "token": "abc",
+        "user_tags": ["a"],
+    };
+
+    doc.retain(|key, _| key.starts_with("user_"));
+    assert_eq!(
+        doc.keys().collect::<Vec<_>>(),
+        ["user_name", "user_id", "user_tags"]
+    );
+
+    let removed = doc.remove_matching(|_, value| value.as_i32().is_some());
+    assert_eq!(removed, doc! { "user_id": 7 });
+    assert_eq!(doc, doc! { "user_name": "alice", "user_tags": ["a"] });
+
+    assert!(doc.remove_matching(|_, _| false).is_empty());
+    doc.retain(|_, _| false);
+    assert!(doc.is_empty());
+}
+
+#[test]
+fn sanitize_keys() {
+    let mut doc = doc! {
+        "a.b": 1,
+        "$set": 2,
+        "plain": 3,
+        "price$": 4,
+        "nested": { "$x.y": { "c.d": 5 } },
+        "array": [{ "e.f": 6 }, 7],
+    };
+
+    doc.sanitize_keys('_');
+
+    let expected = doc! {
+        "a_b": 1,
+        "_set": 2,
+        "plain": 3,
+        "price$": 4,
+        "nested": { "_x_y": { "c_d": 5 } },
+        "array": [{ "e_f": 6 }, 7],
+    };
+    assert_eq!(doc, expected);
+    assert_eq!(
+        doc.keys().collect::<Vec<_>>(),
+        expected.keys().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn validate() {
+    let doc = doc! {
+        "name": "bson",
+        "count": 3,
+        "tags": ["a", "b"],
+        "extra": true,
+    };
+    let schema = [
+        ("name", ElementType::Utf8String),
+        ("count", ElementType::Integer32Bit),
+        ("tags", ElementType::Array),
+    ];
+    doc.validate(&schema).unwrap();
+
+    let missing = doc! { "name": "bson", "tags": [] };
+    let err = missing.validate(&schema).unwrap_err();
+    assert!(err.to_string().contains("\"count\""), "{}", err);
+
+    let wrong_type = doc! { "name": "bson", "count": 3_i64, "tags": [] };
+    let err = wrong_type.validate(&schema).unwrap_err();
+    assert!(err.to_string().contains("\"count\""), "{}", err);
+}
+
+#[test]
+fn flatten() {
+    let doc = doc! {
+        "a": { "b": 1, "c": { "d": "e" } },
+        "f": [1, [2, 3]],
+        "g": [{ "h": true }, { "i": [Bson::Null] }],
+        "empty_doc": {},
+        "empty_arr": [],
+        "j": 2.5,
+    };
+
+    let flat = doc.flatten();
+    assert_eq!(
+        flat,
+        doc! {
+            "a.b": 1,
+            "a.c.d": "e",
+            "f.0": 1,
+            "f.1.0": 2,
+            "f.1.1": 3,
+            "g.0.h": true,
+            "g.1.i.0": Bson::Null,
+            "empty_doc": {},
+            "empty_arr": [],
+            "j": 2.5,
+        }
+    );
+
+    for (path, value) in &flat {
+        assert_eq!(doc.get_path(path), Some(value));
+    }
+}
+
+#[test]
+fn merge() {
+    let mut doc = doc! {
+        "a": { "b": 1, "c": { "d": 2, "e": 3 } },
+        "f": [1, 2],
+        "g": "kept",
+    };
+
+    doc.merge(doc! {
+        "a": { "c": { "d": 4 }, "h": 5 },
+        "f": [3],
+        "i": { "j": 6 },
+    });
+
+    assert_eq!(
+        doc,
+        doc! {
+            "a": { "b": 1, "c": { "d": 4, "e": 3 }, "h": 5 },
+            "f": [3],
+            "g": "kept",
+            "i": { "j": 6 },
+        }
+    );
+
+    // A scalar replaces a document and vice versa.
+    doc.merge(doc! { "a": 1, "g": { "k": 7 } });
+    assert_eq!(
+        doc,
+        doc! {
+            "a": 1,
+            "f": [3],
+            "g": { "k": 7 },
+            "i": { "j": 6 },
+        }
+    );
+}
+
+#[test]
+fn document_builder() {
+    let id = ObjectId::with_string("53e37d08776f724e42000000").unwrap();
+    let built = DocumentBuilder::new()
+        .str("name", "bson")
+        .i32("stars", 10)
+        .i64("downloads", 1 << 40)
+        .f64("ratio", 0.5)
+        .bool("stable", false)
+        .null("license")
+        .object_id("_id", id.clone())
+        .doc("owner", doc! { "name": "mongodb" })
+        .array("tags", vec![Bson::from("serde")])
+        .field("stamp", TimeStamp::new(1, 2))
+        .build();
+
+    assert_eq!(
+        built,
+        doc! {
+            "name": "bson",
+            "stars": 10,
+            "downloads": 1_i64 << 40,
+            "ratio": 0.5,
+            "stable": false,
+            "license": Bson::Null,
+            "_id": id,
+            "owner": { "name": "mongodb" },
+            "tags": ["serde"],
+            "stamp": TimeStamp::new(1, 2),
+        }
+    );
+
+    assert_eq!(DocumentBuilder::new().build(), Document::new());
+}
@@ -0,0 +1,156 @@
+use std::{fs, io::Cursor, path::Path};
+
+use bson::{
+    decode_document,
+    from_slice,
+    spec::{BinarySubtype, ElementType},
+    to_vec,
+    DecoderError,
+    Document,
+};
+use serde_derive::Deserialize;
+
+#[test]
+fn element_type_tags() {
+    let mut tags: Vec<u8> = (0x01..=0x13).collect();
+    tags.push(0x7F);
+    tags.push(0xFF);
+
+    for tag in tags {
+        let element_type = ElementType::from(tag).unwrap();
+        assert_eq!(element_type.as_u8(), tag);
+    }
+
+    assert_eq!(ElementType::from(0x10), Some(ElementType::Integer32Bit));
+    assert_eq!(ElementType::Integer32Bit.as_u8(), 0x10);
+    assert_eq!(ElementType::from(0x00), None);
+    assert_eq!(ElementType::from(0x20), None);
+}
+
+#[test]
+fn user_defined_binary_subtype() {
+    let subtype = BinarySubtype::user_defined(0x80).unwrap();
+    assert_eq!(subtype, BinarySubtype::UserDefined(0x80));
+    assert!(subtype.is_user_defined());
+    assert!(BinarySubtype::user_defined(0xFF).unwrap().is_user_defined());
+
+    assert!(matches!(
+        BinarySubtype::user_defined(0x7F),
+        Err(DecoderError::InvalidValue(_))
+    ));
+    assert!(BinarySubtype::user_defined(0x04).is_err());
+
+    assert!(!BinarySubtype::Generic.is_user_defined());
+    assert!(!BinarySubtype::from(0x06).is_user_defined());
+}
+
+// A file of binary encoding cases, laid out like the BSON corpus in the MongoDB specifications
+// repository but limited to the fields below. Any other field, such as the corpus's extended JSON
+// or `parseErrors`, is rejected rather than silently ignored.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CaseFile {
+    description: String,
+    #[allow(dead_code)]
+    bson_type: Option<String>,
+    #[allow(dead_code)]
+    test_key: Option<String>,
+    #[serde(default)]
+    valid: Vec<ValidCase>,
+    #[serde(default, rename = "decodeErrors")]
+    decode_errors: Vec<DecodeErrorCase>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidCase {
+    description: String,
+    canonical_bson: String,
+    degenerate_bson: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DecodeErrorCase {
+    description: String,
+    bson: String,
+}
+
+// Runs one valid case, returning a description of the first mismatch.
+fn run_valid(case: &ValidCase) -> Result<(), String> {
+    let canonical = hex::decode(&case.canonical_bson).unwrap();
+
+    // Compare re-encoded bytes rather than documents, so that NaN payloads count.
+    let doc = decode_document(&mut Cursor::new(&canonical))
+        .map_err(|e| format!("decode_document failed: {}", e))?;
+    if to_vec(&doc).unwrap() != canonical {
+        return Err("decode_document did not round-trip".to_owned());
+    }
+
+    let (doc, read): (Document, usize) =
+        from_slice(&canonical).map_err(|e| format!("from_slice failed: {}", e))?;
+    if read != canonical.len() || to_vec(&doc).unwrap() != canonical {
+        return Err("from_slice did not round-trip".to_owned());
+    }
+
+    if let Some(ref degenerate) = case.degenerate_bson {
+        let degenerate = hex::decode(degenerate).unwrap();
+        let doc = decode_document(&mut Cursor::new(&degenerate))
+            .map_err(|e| format!("decode_document failed on degenerate bson: {}", e))?;
+        if to_vec(&doc).unwrap() != canonical {
+            return Err("degenerate bson did not encode to the canonical bson".to_owned());
+        }
+    }
+
+    Ok(())
+}
+
+// Runs one decode error case, returning a description of any decoder that accepted it.
+fn run_decode_error(case: &DecodeErrorCase) -> Result<(), String> {
+    let bytes = hex::decode(&case.bson).unwrap();
+
+    if decode_document(&mut Cursor::new(&bytes)).is_ok() {
+        return Err("decode_document accepted invalid bson".to_owned());
+    }
+    if from_slice::<Document>(&bytes).is_ok() {
+        return Err("from_slice accepted invalid bson".to_owned());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn binary_cases() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/spec/json/bson-binary-cases");
+    let mut files = 0;
+    let mut failures = Vec::new();
+
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        files += 1;
+
+        let file: CaseFile = serde_json::from_slice(&fs::read(&path).unwrap())
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+
+        let valid = file
+            .valid
+            .iter()
+            .map(|case| (&case.description, run_valid(case)));
+        let decode_errors = file
+            .decode_errors
+            .iter()
+            .map(|case| (&case.description, run_decode_error(case)));
+
+        for (description, result) in valid.chain(decode_errors) {
+            if let Err(e) = result {
+                failures.push(format!("{}: {}: {}", file.description, description, e));
+            }
+        }
+    }
+
+    assert!(files > 0);
+    assert!(failures.is_empty(), "{:#?}", failures);
+}
@@ -103,6 +103,42 @@ fn test_de_timestamp() {
     );
 }
 
+#[test]
+fn test_timestamp_roundtrip() {
+    use bson::TimeStamp;
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct Foo {
+        ts: TimeStamp,
+    }
+
+    let foo = Foo {
+        ts: TimeStamp::new(12, 10),
+    };
+
+    // Through an encoded document, read back with the raw decoder.
+    let doc = bson::to_bson(&foo).unwrap();
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, doc.as_document().unwrap()).unwrap();
+    let (decoded, _): (Foo, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(decoded, foo);
+
+    // Through JSON, which uses the legacy `{ "t": .., "i": .. }` form.
+    let json = serde_json::to_value(&foo).unwrap();
+    assert_eq!(json, serde_json::json!({ "ts": { "t": 12, "i": 10 } }));
+    let decoded: Foo = bson::from_bson(Bson::from(json)).unwrap();
+    assert_eq!(decoded, foo);
+
+    // The canonical extended JSON `$timestamp` form is accepted too.
+    let json = serde_json::json!({ "ts": { "$timestamp": { "t": 12, "i": 10 } } });
+    let decoded: Foo = bson::from_bson(Bson::from(json)).unwrap();
+    assert_eq!(decoded, foo);
+    assert_eq!(
+        Bson::from_extended_document(doc! { "$timestamp": { "t": 12, "i": 10 } }),
+        Bson::TimeStamp(foo.ts)
+    );
+}
+
 #[test]
 fn test_ser_regex() {
     use bson::Regex;
@@ -153,6 +189,30 @@ fn test_de_regex() {
     assert_eq!(foo.regex, regex);
 }
 
+#[test]
+fn test_regex_roundtrip() {
+    use bson::Regex;
+
+    let regex = Regex {
+        pattern: "^ab+c$".into(),
+        options: "im".into(),
+    };
+
+    // Through an encoded document, read back with the raw decoder.
+    let buf = bson::to_vec(&doc! { "regex": Bson::Regex(regex.clone()) }).unwrap();
+    let (decoded, _): (BTreeMap<String, Regex>, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(decoded["regex"], regex);
+
+    // Through JSON, which uses the extended JSON form.
+    let json = serde_json::to_value(&regex).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "$regex": "^ab+c$", "$options": "im" })
+    );
+    let decoded: Regex = bson::from_bson(Bson::from(json)).unwrap();
+    assert_eq!(decoded, regex);
+}
+
 #[test]
 fn test_ser_code_with_scope() {
     use bson::JavaScriptCodeWithScope;
@@ -203,6 +263,36 @@ fn test_de_code_with_scope() {
     assert_eq!(foo.code_with_scope, code_with_scope);
 }
 
+#[test]
+fn test_code_with_scope_roundtrip_from_bytes() {
+    use bson::JavaScriptCodeWithScope;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Foo {
+        empty: JavaScriptCodeWithScope,
+        nested: JavaScriptCodeWithScope,
+    }
+
+    let foo = Foo {
+        empty: JavaScriptCodeWithScope::new("1", doc! {}),
+        nested: JavaScriptCodeWithScope::new(
+            "function() { return x + y.z; }",
+            doc! { "x": 1, "y": { "z": [1, { "w": "foo" }] } },
+        ),
+    };
+
+    let doc = bson::to_bson(&foo).unwrap();
+    let buf = bson::to_vec(doc.as_document().unwrap()).unwrap();
+
+    // The length of the empty code with scope, which directly follows the element type and the
+    // "empty" cstring, covers itself, the code string "1" and the empty scope document.
+    assert_eq!(buf[11..15], [4 + (4 + 2) + 5, 0, 0, 0]);
+
+    let (decoded, read): (Foo, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(read, buf.len());
+    assert_eq!(decoded, foo);
+}
+
 #[test]
 fn test_ser_datetime() {
     use bson::UtcDateTime;
@@ -233,6 +323,38 @@ fn test_ser_datetime() {
     assert_eq!(xfoo, foo);
 }
 
+#[test]
+fn test_serde_datetime_roundtrip() {
+    use bson::UtcDateTime;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Foo {
+        date: UtcDateTime,
+    }
+
+    let foo = Foo {
+        date: UtcDateTime::from_millis(1_530_492_218_999),
+    };
+
+    let x = bson::to_bson(&foo).unwrap();
+    assert_eq!(x, bson!({ "date": (Bson::UtcDatetime(foo.date.0)) }));
+    let xfoo: Foo = bson::from_bson(x.clone()).unwrap();
+    assert_eq!(xfoo, foo);
+
+    let mut bytes = Vec::new();
+    bson::encode_document(&mut bytes, x.as_document().unwrap()).unwrap();
+    let (xfoo, _): (Foo, usize) = bson::from_slice(&bytes).unwrap();
+    assert_eq!(xfoo, foo);
+
+    let json = serde_json::to_value(&foo).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "date": { "$date": { "$numberLong": 1_530_492_218_999i64 } } })
+    );
+    let xfoo: Foo = bson::from_bson(Bson::from(json)).unwrap();
+    assert_eq!(xfoo, foo);
+}
+
 #[test]
 fn test_compat_u2f() {
     #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
@@ -345,6 +467,27 @@ fn test_binary_helper_non_generic_roundtrip() {
     assert_eq!(x, f);
 }
 
+#[test]
+fn test_binary_helper_roundtrip() {
+    for subtype in [BinarySubtype::Generic, BinarySubtype::Uuid].iter() {
+        let binary = Binary {
+            subtype: *subtype,
+            bytes: b"12345abcde".to_vec(),
+        };
+
+        // Through an encoded document, read back with the raw decoder.
+        let buf = bson::to_vec(&doc! { "data": Bson::Binary(binary.clone()) }).unwrap();
+        let (decoded, _): (BTreeMap<String, Binary>, usize) = bson::from_slice(&buf).unwrap();
+        assert_eq!(decoded["data"], binary);
+
+        // Through JSON, which uses the extended JSON form.
+        let json = serde_json::to_value(&binary).unwrap();
+        assert!(json.get("$binary").is_some());
+        let decoded: Binary = bson::from_bson(Bson::from(json)).unwrap();
+        assert_eq!(decoded, binary);
+    }
+}
+
 #[test]
 fn test_byte_vec() {
     #[derive(Serialize, Debug, Eq, PartialEq)]
@@ -519,3 +662,671 @@ fn test_de_db_pointer() {
 
     assert_eq!(foo.db_pointer, db_pointer.clone());
 }
+
+#[test]
+fn test_de_db_pointer_from_bytes() {
+    use bson::DbPointer;
+    use std::io::Cursor;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Foo {
+        db_pointer: DbPointer,
+    }
+
+    let db_pointer = Bson::from_extended_document(doc! {
+        "$dbPointer": {
+            "$ref": "db.coll",
+            "$id": "507f1f77bcf86cd799439011"
+        }
+    });
+    let db_pointer = db_pointer.as_db_pointer().unwrap();
+
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc! { "db_pointer": db_pointer.clone() }).unwrap();
+
+    let decoded = bson::decode_document(&mut Cursor::new(buf)).unwrap();
+    let foo: Foo = bson::from_bson(Bson::Document(decoded)).unwrap();
+
+    assert_eq!(foo.db_pointer, db_pointer.clone());
+}
+
+#[test]
+fn test_de_code_and_symbol_from_bytes() {
+    use std::io::Cursor;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Foo {
+        code: Bson,
+        symbol: Bson,
+    }
+
+    let code = Bson::JavaScriptCode("function() { return 1; }".to_owned());
+    let symbol = Bson::Symbol("sym".to_owned());
+
+    let mut buf = Vec::new();
+    bson::encode_document(
+        &mut buf,
+        &doc! { "code": code.clone(), "symbol": symbol.clone() },
+    )
+    .unwrap();
+
+    let decoded = bson::decode_document(&mut Cursor::new(buf)).unwrap();
+    let foo: Foo = bson::from_bson(Bson::Document(decoded)).unwrap();
+
+    assert_eq!(foo.code, code);
+    assert_eq!(foo.symbol, symbol);
+}
+
+#[test]
+fn test_de_map_from_bytes() {
+    use std::{collections::HashMap, io::Cursor};
+
+    let doc = doc! { "b": "two", "a": 1, "c": { "d": [1, 2] } };
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc).unwrap();
+
+    let decoded = bson::decode_document(&mut Cursor::new(&buf)).unwrap();
+    let map: BTreeMap<String, Bson> = bson::from_bson(Bson::Document(decoded)).unwrap();
+
+    let keys: Vec<_> = map.keys().map(String::as_str).collect();
+    assert_eq!(keys, ["a", "b", "c"]);
+    assert_eq!(map["a"], Bson::I32(1));
+    assert_eq!(map["b"], Bson::String("two".to_owned()));
+    assert_eq!(map["c"], bson!({ "d": [1, 2] }));
+
+    let ints = doc! { "x": 1, "y": -2 };
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &ints).unwrap();
+
+    let decoded = bson::decode_document(&mut Cursor::new(&buf)).unwrap();
+    let map: HashMap<String, i32> = bson::from_bson(Bson::Document(decoded)).unwrap();
+
+    let mut expected = HashMap::new();
+    expected.insert("x".to_owned(), 1);
+    expected.insert("y".to_owned(), -2);
+    assert_eq!(map, expected);
+}
+
+#[test]
+fn test_de_borrowed_from_bytes() {
+    use bson::RawDecoder;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Foo<'a> {
+        name: &'a str,
+        #[serde(borrow)]
+        data: &'a [u8],
+        count: i32,
+        nested: Vec<&'a str>,
+    }
+
+    let doc = doc! {
+        "name": "borrowed",
+        "data": Binary { subtype: BinarySubtype::Generic, bytes: vec![1, 2, 3] },
+        "count": 3,
+        "nested": ["x", "y"],
+    };
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc).unwrap();
+
+    let foo = Foo::deserialize(&mut RawDecoder::new(&buf)).unwrap();
+    assert_eq!(
+        foo,
+        Foo {
+            name: "borrowed",
+            data: &[1, 2, 3],
+            count: 3,
+            nested: vec!["x", "y"],
+        }
+    );
+
+    // The borrowed fields point into the input buffer rather than into fresh allocations.
+    let range = buf.as_ptr_range();
+    assert!(range.contains(&foo.name.as_ptr()));
+    assert!(range.contains(&foo.data.as_ptr()));
+
+    // Types without a borrowed representation still decode like `from_bson`.
+    let doc = doc! { "oid": bson::oid::ObjectId::new(), "n": Bson::Null };
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc).unwrap();
+    assert_eq!(
+        bson::Document::deserialize(&mut RawDecoder::new(&buf)).unwrap(),
+        doc
+    );
+}
+
+#[test]
+fn test_de_from_slice_back_to_back() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Entry {
+        id: i32,
+        name: String,
+    }
+
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc! { "id": 1, "name": "first" }).unwrap();
+    let first_len = buf.len();
+    bson::encode_document(&mut buf, &doc! { "id": 2, "name": "second" }).unwrap();
+
+    let (first, read): (Entry, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(read, first_len);
+    assert_eq!(
+        first,
+        Entry {
+            id: 1,
+            name: "first".to_owned(),
+        }
+    );
+
+    let (second, read): (Entry, usize) = bson::from_slice(&buf[first_len..]).unwrap();
+    assert_eq!(read, buf.len() - first_len);
+    assert_eq!(
+        second,
+        Entry {
+            id: 2,
+            name: "second".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn test_de_raw_decoder_bytes_read() {
+    let doc = doc! { "a": 1, "b": { "c": [true, "d"] }, "e": 2.5 };
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc).unwrap();
+    let len = buf.len();
+    buf.extend_from_slice(&[0xff; 8]);
+
+    let mut decoder = bson::RawDecoder::new(&buf);
+    assert_eq!(decoder.bytes_read(), 0);
+    let decoded = bson::Document::deserialize(&mut decoder).unwrap();
+    assert_eq!(decoded, doc);
+    assert_eq!(decoder.bytes_read(), len);
+}
+
+#[test]
+fn test_de_top_level_array_from_bytes() {
+    use bson::RawDecoder;
+
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc! { "0": "a", "1": "b" }).unwrap();
+
+    let mut decoder = RawDecoder::for_array(&buf);
+    let vec = Vec::<String>::deserialize(&mut decoder).unwrap();
+    assert_eq!(vec, ["a", "b"]);
+    assert_eq!(decoder.bytes_read(), buf.len());
+
+    // The same bytes still decode as a document by default.
+    assert_eq!(
+        bson::Document::deserialize(&mut RawDecoder::new(&buf)).unwrap(),
+        doc! { "0": "a", "1": "b" }
+    );
+
+    // Keys that aren't consecutive indexes are rejected.
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc! { "0": "a", "2": "b" }).unwrap();
+    assert!(Vec::<String>::deserialize(&mut RawDecoder::for_array(&buf)).is_err());
+}
+
+#[test]
+fn test_de_enum_from_bytes() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Point(i32, i32),
+        Rect { width: i32, height: i32 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Foo {
+        shapes: Vec<Shape>,
+    }
+
+    let foo = Foo {
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Point(1, 2),
+            Shape::Rect {
+                width: 3,
+                height: 4,
+            },
+        ],
+    };
+
+    let doc = bson::to_bson(&foo).unwrap();
+    assert_eq!(
+        doc,
+        bson!({
+            "shapes": [
+                "Empty",
+                { "Circle": 1.5 },
+                { "Point": [1, 2] },
+                { "Rect": { "width": 3, "height": 4 } },
+            ]
+        })
+    );
+
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, doc.as_document().unwrap()).unwrap();
+    let (decoded, _): (Foo, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(decoded, foo);
+
+    // An externally tagged variant must be the only field of its document.
+    let mut buf = Vec::new();
+    bson::encode_document(
+        &mut buf,
+        &doc! { "shapes": [{ "Circle": 1.5, "Empty": null }] },
+    )
+    .unwrap();
+    assert!(bson::from_slice::<Foo>(&buf).is_err());
+
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc! { "shapes": [{ "Point": [1, 2, 3] }] }).unwrap();
+    assert!(bson::from_slice::<Foo>(&buf).is_err());
+}
+
+#[test]
+fn test_de_option_from_bytes() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Foo {
+        present: Option<i32>,
+        null: Option<i32>,
+        undefined: Option<String>,
+        missing: Option<i32>,
+    }
+
+    let mut buf = Vec::new();
+    bson::encode_document(
+        &mut buf,
+        &doc! { "present": 5, "null": Bson::Null, "undefined": Bson::Undefined },
+    )
+    .unwrap();
+
+    let (foo, _): (Foo, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(
+        foo,
+        Foo {
+            present: Some(5),
+            null: None,
+            undefined: None,
+            missing: None,
+        }
+    );
+}
+
+#[test]
+fn test_de_newtype_struct_from_bytes() {
+    use bson::oid::ObjectId;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Wrapper(i32);
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct UserId(ObjectId);
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Foo {
+        count: Wrapper,
+        user: UserId,
+    }
+
+    let oid = ObjectId::with_string("53e37d08776f724e42000000").unwrap();
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc! { "count": 12, "user": oid.clone() }).unwrap();
+
+    let (foo, _): (Foo, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(
+        foo,
+        Foo {
+            count: Wrapper(12),
+            user: UserId(oid),
+        }
+    );
+}
+
+#[test]
+fn test_de_integer_widening() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Wide {
+        a: i64,
+        b: u64,
+        c: u32,
+        d: f64,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Narrow {
+        a: u32,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Small {
+        a: i8,
+    }
+
+    let doc = doc! { "a": 5, "b": 6, "c": 7, "d": 8 };
+    let expected = Wide {
+        a: 5,
+        b: 6,
+        c: 7,
+        d: 8.0,
+    };
+
+    let buf = bson::to_vec(&doc).unwrap();
+    let (wide, _): (Wide, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(wide, expected);
+    let wide: Wide = bson::from_bson(Bson::Document(doc)).unwrap();
+    assert_eq!(wide, expected);
+
+    // Values that don't fit the target type are rejected rather than wrapped.
+    for doc in [doc! { "a": -1 }, doc! { "a": i64::from(u32::MAX) + 1 }] {
+        let buf = bson::to_vec(&doc).unwrap();
+        assert!(bson::from_slice::<Narrow>(&buf).is_err());
+        assert!(bson::from_bson::<Narrow>(Bson::Document(doc)).is_err());
+    }
+
+    let buf = bson::to_vec(&doc! { "a": 128 }).unwrap();
+    assert!(bson::from_slice::<Small>(&buf).is_err());
+}
+
+#[test]
+fn test_de_undefined_and_db_pointer_into_bson_from_bytes() {
+    use bson::{oid::ObjectId, DbPointer};
+
+    let doc = doc! {
+        "undefined": Bson::Undefined,
+        "db_pointer": (DbPointer::new(
+            "db.coll",
+            ObjectId::with_string("507f1f77bcf86cd799439011").unwrap(),
+        )),
+        "nested": { "undefined": Bson::Undefined },
+    };
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc).unwrap();
+
+    let (bson, _): (Bson, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(bson, Bson::Document(doc.clone()));
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Fields {
+        undefined: Bson,
+        db_pointer: Bson,
+        nested: Bson,
+    }
+
+    let (fields, _): (Fields, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(fields.undefined, Bson::Undefined);
+    assert_eq!(&fields.db_pointer, doc.get("db_pointer").unwrap());
+    assert_eq!(&fields.nested, doc.get("nested").unwrap());
+
+    let decoded = bson::from_bson::<Bson>(Bson::Document(doc.clone())).unwrap();
+    assert_eq!(decoded, Bson::Document(doc));
+}
+
+#[test]
+fn test_de_from_slice_validates_document_length() {
+    use bson::{DecoderError, Document};
+
+    // { "a": 1 }
+    let valid = [12, 0, 0, 0, 16, 97, 0, 1, 0, 0, 0, 0];
+    let (doc, read) = bson::from_slice::<Document>(&valid).unwrap();
+    assert_eq!(doc, doc! { "a": 1 });
+    assert_eq!(read, valid.len());
+
+    let mut missing_terminator = valid;
+    missing_terminator[11] = 1;
+    match bson::from_slice::<Document>(&missing_terminator) {
+        Err(DecoderError::SyntaxError(_)) => {}
+        other => panic!("expected syntax error, got {:?}", other),
+    }
+
+    for &(len, bytes_read) in &[(11, 12), (13, 12)] {
+        let mut mismatched = valid.to_vec();
+        mismatched[0] = len;
+        mismatched.push(0);
+
+        match bson::from_slice::<Document>(&mismatched) {
+            Err(DecoderError::InvalidLength(l, message)) => {
+                assert_eq!(l, len as usize);
+                assert!(message.contains(&format!("{} bytes read", bytes_read)));
+            }
+            other => panic!("expected invalid length, got {:?}", other),
+        }
+    }
+
+    let mut too_long = valid;
+    too_long[0] = 50;
+    match bson::from_slice::<Document>(&too_long) {
+        Err(DecoderError::InvalidLength(50, _)) => {}
+        other => panic!("expected invalid length, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_de_generic_binary_into_bytes() {
+    use serde_bytes::ByteBuf;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Foo {
+        #[serde(with = "serde_bytes")]
+        vec: Vec<u8>,
+        buf: ByteBuf,
+        array: [u8; 4],
+    }
+
+    let data = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes: vec![1, 2, 3, 4],
+    };
+    let doc = doc! { "vec": data.clone(), "buf": data.clone(), "array": data };
+    let expected = Foo {
+        vec: vec![1, 2, 3, 4],
+        buf: ByteBuf::from(vec![1, 2, 3, 4]),
+        array: [1, 2, 3, 4],
+    };
+
+    let foo: Foo = bson::from_bson(Bson::Document(doc.clone())).unwrap();
+    assert_eq!(foo, expected);
+
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc).unwrap();
+    let (foo, _): (Foo, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(foo, expected);
+
+    // A fixed-size array only accepts binary of exactly its length.
+    #[derive(Deserialize, Debug)]
+    struct Short {
+        #[allow(dead_code)]
+        array: [u8; 3],
+    }
+
+    let doc = doc! {
+        "array": Binary { subtype: BinarySubtype::Generic, bytes: vec![1, 2, 3, 4] },
+    };
+    assert!(bson::from_bson::<Short>(Bson::Document(doc.clone())).is_err());
+
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc).unwrap();
+    assert!(bson::from_slice::<Short>(&buf).is_err());
+}
+
+#[test]
+fn test_de_rename_all_from_bytes() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Account {
+        user_name: String,
+        login_count: i32,
+        last_seen: String,
+    }
+
+    let doc = doc! {
+        "userName": "ada",
+        "unknownField": { "skipped": [1, 2, 3] },
+        "loginCount": 12,
+        "lastSeen": "yesterday",
+        "trailing": Binary { subtype: BinarySubtype::Generic, bytes: vec![0; 16] },
+    };
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, &doc).unwrap();
+
+    let (account, read): (Account, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(read, buf.len());
+    assert_eq!(
+        account,
+        Account {
+            user_name: "ada".to_owned(),
+            login_count: 12,
+            last_seen: "yesterday".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn test_de_ignored_fields_from_bytes() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Small {
+        id: i32,
+    }
+
+    let large = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes: vec![0xAB; 8 * 1024 * 1024],
+    };
+    let mut buf = Vec::new();
+    bson::encode_document(
+        &mut buf,
+        &doc! {
+            "blob": large,
+            "id": 7,
+            "nested": { "a": [1, 2, { "b": "c" }] },
+            "code": Bson::JavaScriptCodeWithScope(bson::JavaScriptCodeWithScope {
+                code: "x".to_owned(),
+                scope: doc! { "x": 1 },
+            }),
+        },
+    )
+    .unwrap();
+
+    let (small, read): (Small, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(small, Small { id: 7 });
+    assert_eq!(read, buf.len());
+
+    // Ignored values are skipped by length rather than decoded, so an element type that could not
+    // be decoded inside an ignored document does not cause an error.
+    // { "id": 7, "skip": { "x": <0x7a> } }
+    let buf = [
+        28, 0, 0, 0, 16, b'i', b'd', 0, 7, 0, 0, 0, 3, b's', b'k', b'i', b'p', 0, 9, 0, 0, 0, 0x7a,
+        b'x', 0, 0, 0, 0,
+    ];
+    let (small, _): (Small, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(small, Small { id: 7 });
+    assert!(bson::from_slice::<bson::Document>(&buf).is_err());
+}
+
+#[test]
+fn test_ser_bson_roundtrip_all_variants() {
+    use bson::{oid::ObjectId, DbPointer, JavaScriptCodeWithScope, Regex, TimeStamp, UtcDateTime};
+    use serde_test::{assert_ser_tokens, Configure, Token};
+
+    let values = vec![
+        Bson::FloatingPoint(1.5),
+        Bson::String("s".to_owned()),
+        bson!([1_i64, "two"]),
+        bson!({ "a": 1_i64 }),
+        Bson::Boolean(true),
+        Bson::Null,
+        Bson::Regex(Regex::new("^a", "i")),
+        Bson::JavaScriptCode("x".to_owned()),
+        Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope::new("x", doc! { "x": 1_i64 })),
+        Bson::I32(1),
+        Bson::I64(1 << 40),
+        Bson::TimeStamp(TimeStamp::new(1, 2)),
+        Bson::Binary(Binary::generic(vec![1, 2, 3])),
+        Bson::Binary(Binary::new(BinarySubtype::Md5, vec![1, 2, 3])),
+        Bson::ObjectId(ObjectId::with_string("507f1f77bcf86cd799439011").unwrap()),
+        Bson::UtcDatetime(UtcDateTime::from_millis(1_500_000_000_000).0),
+        Bson::Symbol("sym".to_owned()),
+        Bson::Undefined,
+        Bson::MinKey,
+        Bson::MaxKey,
+        Bson::DbPointer(DbPointer::new(
+            "db.coll",
+            ObjectId::with_string("507f1f77bcf86cd799439011").unwrap(),
+        )),
+    ];
+
+    for value in values {
+        assert_eq!(bson::to_bson(&value).unwrap(), value, "to_bson");
+        let doc = doc! { "v": value.clone() };
+        let mut buf = Vec::new();
+        bson::encode_document(
+            &mut buf,
+            bson::to_bson(&doc).unwrap().as_document().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(bson::decode_document(&mut &buf[..]).unwrap(), doc, "bytes");
+
+        // Relaxed extended JSON writes integers as plain numbers, which read back as 64-bit.
+        if let Bson::I32(v) = value {
+            assert_eq!(serde_json::to_value(&value).unwrap(), serde_json::json!(v));
+            continue;
+        }
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            Bson::from_extended_json_str(&json).unwrap(),
+            value,
+            "json {}",
+            json
+        );
+    }
+
+    // Only `Encoder` gets native values. Other formats, human-readable or not, see through the
+    // newtype struct to the extended JSON document, apart from generic binary in compact formats.
+    assert!(serde::Serializer::is_human_readable(&bson::Encoder::new()));
+
+    let min_key = [
+        Token::NewtypeStruct {
+            name: "$__bson_Bson",
+        },
+        Token::Map { len: Some(1) },
+        Token::Str("$minKey"),
+        Token::I32(1),
+        Token::MapEnd,
+    ];
+    assert_ser_tokens(&Bson::MinKey.readable(), &min_key);
+    assert_ser_tokens(&Bson::MinKey.compact(), &min_key);
+    assert_ser_tokens(
+        &Bson::Binary(Binary::generic(vec![1, 2])).compact(),
+        &[Token::Bytes(&[1, 2])],
+    );
+}
+
+#[test]
+fn test_human_readable_dependent_types_roundtrip() {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    // Types like these serialize as strings only for human-readable formats, so encoding and
+    // decoding have to agree on that.
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Foo {
+        ip: Ipv4Addr,
+        addr: SocketAddr,
+    }
+
+    let foo = Foo {
+        ip: Ipv4Addr::new(127, 0, 0, 1),
+        addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 27017),
+    };
+
+    let bson = bson::to_bson(&foo).unwrap();
+    assert_eq!(bson, bson!({ "ip": "127.0.0.1", "addr": "10.0.0.1:27017" }));
+    assert_eq!(bson::from_bson::<Foo>(bson.clone()).unwrap(), foo);
+
+    let mut buf = Vec::new();
+    bson::encode_document(&mut buf, bson.as_document().unwrap()).unwrap();
+    let (decoded, _): (Foo, usize) = bson::from_slice(&buf).unwrap();
+    assert_eq!(decoded, foo);
+}